@@ -0,0 +1,96 @@
+//! Measures the cost of one tick of collision avoidance for a crowd the size of the 1000 pawn
+//! stress test in the dev tools, with each pawn avoiding every other pawn within its vision radius.
+
+use bevy::prelude::*;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use pb_engine::pawn::{
+    MovementStats, Pawn,
+    ai::path::avoidance::{self, Agent},
+};
+
+const PAWNS: usize = 1000;
+
+struct Crowd {
+    agents: Vec<Agent>,
+    preferred: Vec<Vec2>,
+    neighbours: Vec<Vec<Agent>>,
+}
+
+impl Crowd {
+    /// Places pawns in a square grid, each heading through the centre to the opposite side.
+    fn new(spacing: f32) -> Self {
+        let max_velocity = MovementStats::DEFAULT.max_velocity;
+        let columns = (PAWNS as f32).sqrt().ceil() as usize;
+        let centre = Vec2::splat((columns - 1) as f32 * spacing / 2.);
+
+        let agents: Vec<Agent> = (0..PAWNS)
+            .map(|index| {
+                let position =
+                    Vec2::new((index % columns) as f32, (index / columns) as f32) * spacing;
+                Agent {
+                    position,
+                    velocity: (centre - position).normalize_or_zero() * max_velocity,
+                    radius: Pawn::RADIUS,
+                }
+            })
+            .collect();
+        let preferred = agents.iter().map(|agent| agent.velocity).collect();
+        let neighbours = agents
+            .iter()
+            .enumerate()
+            .map(|(index, agent)| {
+                agents
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other_index, other)| {
+                        other_index != index
+                            && other.position.distance(agent.position) < Pawn::VISION_RADIUS
+                    })
+                    .map(|(_, &other)| other)
+                    .collect()
+            })
+            .collect();
+
+        Crowd {
+            agents,
+            preferred,
+            neighbours,
+        }
+    }
+
+    fn tick(&self, time_step: f32) -> Vec2 {
+        let max_velocity = MovementStats::DEFAULT.max_velocity;
+        self.agents
+            .iter()
+            .zip(&self.preferred)
+            .zip(&self.neighbours)
+            .map(|((agent, &preferred), neighbours)| {
+                avoidance::velocity(
+                    agent,
+                    preferred,
+                    max_velocity,
+                    time_step,
+                    neighbours.iter().copied(),
+                )
+            })
+            .sum()
+    }
+}
+
+fn bench_avoidance(c: &mut Criterion) {
+    let time_step = Time::<Fixed>::default().timestep().as_secs_f32();
+
+    let mut group = c.benchmark_group("avoidance");
+    for spacing in [2., 1., 0.5] {
+        let crowd = Crowd::new(spacing);
+        group.bench_with_input(
+            BenchmarkId::new("crowd", format!("{spacing}m")),
+            &crowd,
+            |b, crowd| b.iter(|| crowd.tick(time_step)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_avoidance);
+criterion_main!(benches);
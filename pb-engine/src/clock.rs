@@ -0,0 +1,159 @@
+use std::{fmt, time::Duration};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{EngineState, root::Root};
+
+/// The in-game time, advanced by the fixed timestep.
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Clock {
+    elapsed: Duration,
+    speed: Speed,
+    paused: bool,
+    /// The real time spent playing the game, including while paused.
+    play_time: Duration,
+}
+
+/// Multiplier applied to the rate at which the simulation runs.
+///
+/// The fixed timestep is unchanged, so faster speeds run more fixed updates per frame rather than
+/// longer ones.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Speed {
+    #[default]
+    Normal,
+    Fast,
+    Fastest,
+}
+
+impl Clock {
+    /// The number of in-game seconds that pass for each second of simulation time.
+    pub const RATE: u32 = 60;
+    pub const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+    pub const HOUR: Duration = Duration::from_secs(60 * 60);
+    pub const MINUTE: Duration = Duration::from_secs(60);
+    pub const START: Duration = Duration::from_secs(8 * 60 * 60);
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn day(&self) -> u32 {
+        (self.elapsed.as_secs() / Clock::DAY.as_secs()) as u32
+    }
+
+    pub fn time_of_day(&self) -> Duration {
+        Duration::from_secs(self.elapsed.as_secs() % Clock::DAY.as_secs())
+    }
+
+    pub fn hour(&self) -> u32 {
+        (self.time_of_day().as_secs() / Clock::HOUR.as_secs()) as u32
+    }
+
+    pub fn minute(&self) -> u32 {
+        (self.time_of_day().as_secs() % Clock::HOUR.as_secs() / Clock::MINUTE.as_secs()) as u32
+    }
+
+    pub fn play_time(&self) -> Duration {
+        self.play_time
+    }
+
+    pub fn speed(&self) -> Speed {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: Speed) {
+        self.speed = speed;
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn toggle_paused(&mut self) {
+        self.paused = !self.paused;
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock {
+            elapsed: Clock::START,
+            speed: Speed::Normal,
+            paused: false,
+            play_time: Duration::ZERO,
+        }
+    }
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {}, {:02}:{:02}",
+            self.day() + 1,
+            self.hour(),
+            self.minute()
+        )
+    }
+}
+
+impl Speed {
+    pub const ALL: [Speed; 3] = [Speed::Normal, Speed::Fast, Speed::Fastest];
+
+    pub fn multiplier(self) -> f32 {
+        match self {
+            Speed::Normal => 1.,
+            Speed::Fast => 2.,
+            Speed::Fastest => 4.,
+        }
+    }
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x", self.multiplier())
+    }
+}
+
+pub fn update(mut clock: ResMut<Clock>, time: Res<Time>) {
+    clock.elapsed += time.delta() * Clock::RATE;
+}
+
+pub fn update_play_time(
+    mut clock: ResMut<Clock>,
+    state: Res<State<EngineState>>,
+    time: Res<Time<Real>>,
+) {
+    if matches!(state.get(), EngineState::Running(_)) {
+        clock.play_time += time.delta();
+    }
+}
+
+/// Applies the clock speed to virtual time, which drives the fixed timestep.
+pub fn update_speed(clock: Res<Clock>, mut time: ResMut<Time<Virtual>>) {
+    if clock.is_paused() {
+        if !time.is_paused() {
+            time.pause();
+        }
+    } else {
+        if time.is_paused() {
+            time.unpause();
+        }
+        if time.relative_speed() != clock.speed().multiplier() {
+            time.set_relative_speed(clock.speed().multiplier());
+        }
+    }
+}
+
+pub fn root_added(_: Trigger<OnAdd, Root>, mut commands: Commands) {
+    commands.insert_resource(Clock::default());
+}
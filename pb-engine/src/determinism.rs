@@ -0,0 +1,25 @@
+use bevy::{ecs::schedule::ExecutorKind, prelude::*, tasks::ComputeTaskPool};
+
+/// Makes the simulation reproducible, so the same save and inputs always give the same result on
+/// the same build and platform. Must be inserted before [`PbEnginePlugin`](crate::PbEnginePlugin)
+/// is added.
+///
+/// Random numbers are seeded from the loaded save, every schedule runs its systems one at a time
+/// in a fixed order, and parallel queries are iterated sequentially. The physics solver also
+/// iterates in parallel, so the compute task pool should be limited to a single thread.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct Deterministic;
+
+pub(crate) fn finish(app: &mut App) {
+    if !app.world().contains_resource::<Deterministic>() {
+        return;
+    }
+
+    for (_, schedule) in app.world_mut().resource_mut::<Schedules>().iter_mut() {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    }
+
+    if ComputeTaskPool::try_get().is_some_and(|pool| pool.thread_num() > 1) {
+        warn!("physics may not be deterministic with more than one compute thread");
+    }
+}
@@ -1,25 +1,49 @@
 use avian2d::prelude::*;
 use bevy::{
-    color::palettes::tailwind::{GREEN_300, INDIGO_800},
+    color::palettes::tailwind::{AMBER_500, GREEN_300, GREEN_500, INDIGO_800, RED_500},
     prelude::*,
 };
 
 use crate::{
-    map::mesh::MapMesh,
-    pawn::ai::{Task, path::PathTask},
+    map::{mesh::MapMesh, patrol::PatrolRoute},
+    pawn::ai::{
+        Task,
+        path::PathTask,
+        reservation::{Reservation, UsageSlots},
+    },
 };
 
 #[derive(Default, Resource)]
 pub struct DevSettings {
     pub draw_paths: bool,
     pub draw_meshes: bool,
+    pub draw_reservations: bool,
 }
 
 pub fn draw_paths_condition(settings: Res<DevSettings>) -> bool {
     settings.draw_paths
 }
 
-pub fn draw_paths(task_q: Query<(&Task, &PathTask)>, pos_q: Query<&Position>, mut gizmos: Gizmos) {
+pub fn draw_paths(
+    task_q: Query<(&Task, &PathTask)>,
+    pos_q: Query<&Position>,
+    route_q: Query<&PatrolRoute>,
+    mut gizmos: Gizmos,
+) {
+    for route in &route_q {
+        let waypoints = route.waypoints();
+        gizmos.linestrip_2d(
+            waypoints
+                .iter()
+                .chain(waypoints.first())
+                .map(|waypoint| waypoint.position),
+            AMBER_500,
+        );
+        for waypoint in waypoints {
+            gizmos.circle_2d(waypoint.position, 0.1, AMBER_500);
+        }
+    }
+
     for (task, path) in &task_q {
         if let Some(steps) = path.steps() {
             if let Ok(start) = pos_q.get(task.actor()) {
@@ -34,6 +58,45 @@ pub fn draw_paths(task_q: Query<(&Task, &PathTask)>, pos_q: Query<&Position>, mu
     }
 }
 
+pub fn draw_reservations_condition(settings: Res<DevSettings>) -> bool {
+    settings.draw_reservations
+}
+
+/// Draws the usage slots of objects, and a line from each reserved slot to the pawn reserving it.
+pub fn draw_reservations(
+    object_q: Query<(&UsageSlots, &GlobalTransform)>,
+    task_q: Query<(&Task, &Reservation)>,
+    pos_q: Query<&Position>,
+    mut gizmos: Gizmos,
+) {
+    for (slots, transform) in &object_q {
+        for slot in slots.slots() {
+            let position = transform.transform_point(slot.offset.extend(0.)).xy();
+            let color = if slot.reserved_by().is_some() {
+                RED_500
+            } else {
+                GREEN_500
+            };
+            let facing = transform.rotation().to_euler(EulerRot::ZYX).0 + slot.facing;
+            gizmos.circle_2d(position, 0.1, color);
+            gizmos.line_2d(position, position + Vec2::from_angle(facing) * 0.2, color);
+        }
+    }
+
+    for (task, reservation) in &task_q {
+        let Ok((slots, transform)) = object_q.get(reservation.object()) else {
+            continue;
+        };
+        if let (Some(slot), Ok(actor)) = (
+            slots.slots().get(reservation.slot()),
+            pos_q.get(task.actor()),
+        ) {
+            let point = transform.transform_point(slot.offset.extend(0.)).xy();
+            gizmos.line_2d(actor.0, point, RED_500);
+        }
+    }
+}
+
 pub fn draw_meshes_condition(settings: Res<DevSettings>) -> bool {
     settings.draw_meshes
 }
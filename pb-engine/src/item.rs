@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{map::room::ContainingRoom, pawn::ai::reservation::UsageSlots};
+
+/// An object which can lie on the floor of a map or be carried by a pawn.
+///
+/// Items on the floor are tracked by the [`RoomContents`](crate::map::room::RoomContents) of the
+/// room they are in, while carried items are held in the [`Inventory`] of a pawn instead. Only one
+/// pawn at a time may go to pick up an item on the floor.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+#[require(
+    Transform,
+    Visibility,
+    Name::new("item"),
+    UsageSlots::single(Vec2::ZERO, 0.)
+)]
+pub struct Item {
+    pub kind: ItemKind,
+    /// Whether guards confiscate the item when they find it.
+    pub contraband: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Book,
+    Cigarettes,
+    Drugs,
+    Phone,
+    Shiv,
+    Spoon,
+}
+
+/// The pawn carrying an item.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+#[relationship(relationship_target = Inventory)]
+pub struct HeldBy(pub Entity);
+
+/// The items carried by a pawn.
+#[derive(Component, Default, Debug, PartialEq, Eq)]
+#[relationship_target(relationship = HeldBy, linked_spawn)]
+pub struct Inventory(Vec<Entity>);
+
+impl Item {
+    pub fn new(kind: ItemKind) -> Self {
+        Item {
+            kind,
+            contraband: kind.is_contraband(),
+        }
+    }
+
+    pub fn bundle(self, position: Vec2) -> impl Bundle {
+        (self, Transform::from_translation(position.extend(0.)))
+    }
+}
+
+impl ItemKind {
+    pub const ALL: [ItemKind; 6] = [
+        ItemKind::Book,
+        ItemKind::Cigarettes,
+        ItemKind::Drugs,
+        ItemKind::Phone,
+        ItemKind::Shiv,
+        ItemKind::Spoon,
+    ];
+
+    /// Whether items of this kind are contraband by default.
+    pub fn is_contraband(self) -> bool {
+        matches!(self, ItemKind::Drugs | ItemKind::Phone | ItemKind::Shiv)
+    }
+}
+
+/// Picks up an item, removing it from the floor.
+pub fn pick_up(commands: &mut Commands, pawn: Entity, item: Entity) {
+    commands.entity(item).insert(HeldBy(pawn));
+}
+
+/// Drops an item on the floor at the given position.
+pub fn drop_at(commands: &mut Commands, item: Entity, position: Vec2) {
+    commands
+        .entity(item)
+        .remove::<HeldBy>()
+        .insert(Transform::from_translation(position.extend(0.)));
+}
+
+pub fn held_inserted(trigger: Trigger<OnInsert, HeldBy>, mut commands: Commands) {
+    commands
+        .entity(trigger.target())
+        .try_remove::<ContainingRoom>();
+}
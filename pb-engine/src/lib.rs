@@ -1,21 +1,44 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
+pub mod clock;
+pub mod determinism;
 pub mod dev;
+pub mod item;
 pub mod layer;
 pub mod map;
 pub mod pawn;
+pub mod regime;
 pub mod root;
 pub mod save;
+pub mod security;
+pub mod statistics;
 
 use avian2d::{
     dynamics::{integrator::IntegrationSet, solver::schedule::SubstepSolverSet},
     prelude::*,
 };
-use bevy::prelude::*;
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use clock::Clock;
 use dev::DevSettings;
-use pawn::{Pawn, ai::path::PathQueryConfig};
+use item::Item;
+use pawn::{
+    MovementStats, Pawn,
+    ai::{
+        TaskFailed,
+        interaction::InteractionFinished,
+        path::PathQueryConfig,
+        perception::{PERCEPTION_INTERVAL, PerceptionConfig},
+    },
+    health::Health,
+    mood::Mood,
+    role::{Guard, Prisoner, Worker},
+};
 use pb_util::event::AddComponentEvent;
+use regime::Regime;
 use root::Root;
+use security::SecurityEvent;
+use statistics::Statistics;
+use std::time::Duration;
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
 pub enum EngineState {
@@ -28,7 +51,15 @@ pub struct PbEnginePlugin;
 
 impl Plugin for PbEnginePlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<Root>().register_type::<Pawn>();
+        app.register_type::<Root>()
+            .register_type::<Pawn>()
+            .register_type::<Item>()
+            .register_type::<MovementStats>()
+            .register_type::<Mood>()
+            .register_type::<Health>()
+            .register_type::<Prisoner>()
+            .register_type::<Guard>()
+            .register_type::<Worker>();
 
         app.init_state::<EngineState>();
 
@@ -37,9 +68,20 @@ impl Plugin for PbEnginePlugin {
         app.insert_resource(Gravity::ZERO);
 
         app.init_resource::<PathQueryConfig>()
-            .init_resource::<DevSettings>();
+            .init_resource::<PerceptionConfig>()
+            .init_resource::<DevSettings>()
+            .init_resource::<Clock>()
+            .init_resource::<Regime>()
+            .init_resource::<Statistics>();
+
+        app.add_event::<TaskFailed>()
+            .add_event::<SecurityEvent>()
+            .add_event::<InteractionFinished>();
 
         app.add_observer(root::child_added)
+            .add_observer(clock::root_added)
+            .add_observer(regime::root_added)
+            .add_observer(statistics::root_added)
             .add_observer(map::map_inserted)
             .add_observer(map::room::room_replaced)
             .add_observer(map::door::wall_replaced)
@@ -50,6 +92,13 @@ impl Plugin for PbEnginePlugin {
             .add_observer(pawn::ai::task_added)
             .add_observer(pawn::ai::task_removed)
             .add_observer(pawn::ai::actor_removed)
+            .add_observer(pawn::ai::trace::task_added)
+            .add_observer(pawn::ai::trace::task_removed)
+            .add_observer(pawn::ai::interaction::interaction_removed)
+            .add_observer(pawn::ai::escape::escaping_added)
+            .add_observer(pawn::ai::escape::escaping_removed)
+            .add_observer(pawn::role::prisoner_added)
+            .add_observer(item::held_inserted)
             .add_systems(
                 FixedPreUpdate,
                 (
@@ -63,11 +112,44 @@ impl Plugin for PbEnginePlugin {
                     map::perimeter::add_colliders,
                     map::mesh::update_mesh,
                     map::room::update_containing_room,
+                    clock::update,
                 ),
             )
+            .add_systems(
+                PreUpdate,
+                clock::update_speed.run_if(resource_changed::<Clock>),
+            )
             .add_systems(
                 FixedUpdate,
-                (pawn::ai::path::update, pawn::movement).chain(),
+                (
+                    pawn::ai::perception::update.run_if(on_timer(PERCEPTION_INTERVAL)),
+                    pawn::ai::escape::detect,
+                    pawn::ai::escape::plan.run_if(on_timer(Duration::from_secs(10))),
+                    pawn::ai::medical::seek_treatment.run_if(on_timer(Duration::from_secs(1))),
+                    pawn::ai::regime::update.run_if(on_timer(Duration::from_secs(1))),
+                    pawn::ai::patrol::assign.run_if(on_timer(Duration::from_secs(1))),
+                    pawn::ai::patrol::update,
+                    pawn::ai::interaction::socialise.run_if(on_timer(Duration::from_secs(5))),
+                    pawn::ai::medical::assign.run_if(on_timer(Duration::from_secs(1))),
+                    (pawn::ai::search::assign, pawn::ai::item::scavenge)
+                        .run_if(on_timer(Duration::from_secs(5))),
+                    pawn::ai::path::repath,
+                    pawn::ai::path::update,
+                    pawn::ai::group::update,
+                    pawn::ai::interaction::update,
+                    (
+                        pawn::ai::search::finished,
+                        pawn::ai::search::update,
+                        pawn::ai::item::update,
+                    )
+                        .chain(),
+                    pawn::ai::escape::update,
+                    pawn::ai::medical::heal,
+                    pawn::movement,
+                    statistics::update,
+                    pawn::ai::trace::task_failed,
+                )
+                    .chain(),
             )
             .add_systems(
                 SubstepSchedule,
@@ -78,12 +160,18 @@ impl Plugin for PbEnginePlugin {
             .add_systems(
                 Update,
                 (
+                    clock::update_play_time,
                     dev::draw_meshes.run_if(dev::draw_meshes_condition),
                     dev::draw_paths.run_if(dev::draw_paths_condition),
+                    dev::draw_reservations.run_if(dev::draw_reservations_condition),
                 ),
             );
 
         #[cfg(feature = "dev")]
         app.add_plugins(PhysicsDebugPlugin::default());
     }
+
+    fn finish(&self, app: &mut App) {
+        determinism::finish(app);
+    }
 }
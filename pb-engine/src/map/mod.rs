@@ -1,6 +1,7 @@
 pub mod corner;
 pub mod door;
 pub mod mesh;
+pub mod patrol;
 pub mod perimeter;
 pub mod room;
 pub mod wall;
@@ -16,6 +17,7 @@ use bevy::{
     prelude::*,
 };
 use mesh::MapMesh;
+use rand::{Rng, seq::IteratorRandom};
 use spade::{
     CdtEdge, ConstrainedDelaunayTriangulation, HasPosition, Point2, PositionInTriangulation,
     Triangulation,
@@ -26,7 +28,13 @@ use spade::{
 };
 
 use crate::{
-    map::{corner::Corner, door::Door, perimeter::Perimeter, room::Room, wall::Wall},
+    map::{
+        corner::Corner,
+        door::Door,
+        perimeter::Perimeter,
+        room::{Designation, Room},
+        wall::Wall,
+    },
     save::MapModel,
 };
 
@@ -102,12 +110,29 @@ impl Map {
     }
 
     pub fn from_model(model: &MapModel, entity_map: &mut impl EntityMapper) -> Result<Self> {
+        let outer_room = model.rooms.first().ok_or("map has no rooms")?.id;
         let corner_indices: HashMap<Entity, usize> = model
             .corners
             .iter()
             .enumerate()
             .map(|(index, corner)| (corner.id, index))
             .collect();
+        let wall_corners = model
+            .walls
+            .iter()
+            .map(|wall| {
+                let corner_index = |id| {
+                    corner_indices
+                        .get(&id)
+                        .copied()
+                        .ok_or_else(|| format!("wall {} refers to missing corner {id}", wall.id))
+                };
+                Ok([
+                    corner_index(wall.corners[0])?,
+                    corner_index(wall.corners[1])?,
+                ])
+            })
+            .collect::<Result<Vec<_>, String>>()?;
 
         let mut triangulation = ConstrainedDelaunayTriangulation::<
             VertexData,
@@ -125,24 +150,16 @@ impl Map {
                     )
                 })
                 .collect(),
-            model
-                .walls
-                .iter()
-                .map(|wall| wall.corners.map(|id| corner_indices[&id]))
-                .collect(),
+            wall_corners.clone(),
         )?;
 
         if triangulation.vertices().len() != model.corners.len() {
             return Err("duplicate vertices".into());
         }
 
-        for wall in &model.walls {
-            let [from, to] = wall.corners.map(|id| {
-                triangulation
-                    .fixed_vertices()
-                    .nth(corner_indices[&id])
-                    .unwrap()
-            });
+        for (wall, corners) in model.walls.iter().zip(wall_corners) {
+            let [from, to] =
+                corners.map(|index| triangulation.fixed_vertices().nth(index).unwrap());
 
             let edge = triangulation
                 .get_edge_from_neighbors(from, to)
@@ -160,9 +177,12 @@ impl Map {
                 Some(MapEntity::Owned(entity_map.get_mapped(wall.rooms[1])));
         }
 
-        let outer_room = entity_map.get_mapped(model.rooms[0].id);
+        let outer_room = entity_map.get_mapped(outer_room);
         match &mut triangulation.face_data_mut(OUTER_FACE).room {
-            Some(room) => assert_eq!(room.id(), outer_room),
+            Some(room) if room.id() != outer_room => {
+                return Err("walls on the outside of the map have the wrong room".into());
+            }
+            Some(_) => {}
             room @ None => *room = Some(MapEntity::Owned(outer_room)),
         }
 
@@ -373,6 +393,27 @@ impl Map {
         }
     }
 
+    /// Picks a random point within the interior of a room in this map.
+    pub fn random_point(&self, room: &Room, rng: &mut impl Rng) -> Option<Vec2> {
+        let face = room
+            .faces()
+            .iter()
+            .filter_map(|face| face.as_inner())
+            .choose(rng)?;
+        let [a, b, c] = self
+            .triangulation
+            .face(face)
+            .vertices()
+            .map(|vertex| vertex.data().position);
+
+        let (mut u, mut v) = (rng.random::<f32>(), rng.random::<f32>());
+        if u + v > 1. {
+            (u, v) = (1. - u, 1. - v);
+        }
+
+        Some(a + (b - a) * u + (c - a) * v)
+    }
+
     pub fn insert_corner(&mut self, queries: &mut MapQueries, corner: CornerDef) -> Result<Entity> {
         let vertex = self.get_or_insert_vertex(queries, corner)?;
         self.sync(queries);
@@ -867,6 +908,7 @@ impl MapQueries<'_, '_> {
                             .allow::<Corner>()
                             .allow::<Wall>()
                             .allow::<Room>()
+                            .allow::<Designation>()
                             .allow::<Door>()
                             .allow::<Perimeter>()
                             .linked_cloning(false);
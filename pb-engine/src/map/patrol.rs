@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A loop of waypoints followed by guards on patrol, attached to a map as a child entity.
+#[derive(Clone, Debug, Default, Component, Serialize, Deserialize)]
+#[require(Name::new("Patrol route"))]
+pub struct PatrolRoute {
+    waypoints: Vec<Waypoint>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Waypoint {
+    pub position: Vec2,
+    /// How long guards wait after reaching this waypoint before moving to the next one.
+    #[serde(default, skip_serializing_if = "Duration::is_zero")]
+    pub pause: Duration,
+}
+
+impl PatrolRoute {
+    pub const DEFAULT_PAUSE: Duration = Duration::from_secs(2);
+
+    pub fn new(waypoints: Vec<Waypoint>) -> Self {
+        PatrolRoute { waypoints }
+    }
+
+    pub fn waypoints(&self) -> &[Waypoint] {
+        &self.waypoints
+    }
+
+    pub fn waypoint(&self, index: usize) -> Option<&Waypoint> {
+        if self.waypoints.is_empty() {
+            None
+        } else {
+            Some(&self.waypoints[index % self.waypoints.len()])
+        }
+    }
+
+    pub fn push(&mut self, waypoint: Waypoint) {
+        self.waypoints.push(waypoint);
+    }
+
+    pub fn len(&self) -> usize {
+        self.waypoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+}
+
+impl Waypoint {
+    pub fn new(position: Vec2) -> Self {
+        Waypoint {
+            position,
+            pause: PatrolRoute::DEFAULT_PAUSE,
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use bevy::{
-    ecs::{entity::EntityHashSet, relationship::Relationship},
+    ecs::{entity::EntityHashSet, relationship::Relationship, system::SystemParam},
     prelude::*,
 };
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
 use spade::handles::{FixedFaceHandle, FixedVertexHandle, OUTER_FACE, PossiblyOuterTag};
 
 use crate::{
+    determinism::Deterministic,
+    item::{HeldBy, Item},
     map::{Map, door::RoomLinks},
     pawn::Pawn,
     root::ChildOfRoot,
@@ -29,6 +33,78 @@ pub struct ContainingRoom {
 #[relationship_target(relationship = ContainingRoom)]
 pub struct RoomContents(EntityHashSet);
 
+/// The intended use of a room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Component, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Designation {
+    Cell,
+    Canteen,
+    Workshop,
+    Yard,
+    Infirmary,
+}
+
+#[derive(SystemParam)]
+pub struct DesignationQuery<'w, 's> {
+    room_q: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Room,
+            &'static Designation,
+            &'static ChildOf,
+        ),
+    >,
+    map_q: Query<'w, 's, &'static Map>,
+}
+
+impl DesignationQuery<'_, '_> {
+    pub fn designation(&self, room: Entity) -> Option<Designation> {
+        let (_, _, &designation, _) = self.room_q.get(room).ok()?;
+        Some(designation)
+    }
+
+    /// Picks a random room in the map with the given designation.
+    pub fn random_room(
+        &self,
+        map: Entity,
+        designation: Designation,
+        rng: &mut impl Rng,
+    ) -> Option<Entity> {
+        let rooms: Vec<Entity> = self
+            .room_q
+            .iter()
+            .filter(|&(_, room, &room_designation, parent)| {
+                room_designation == designation && parent.parent() == map && !room.is_outer()
+            })
+            .map(|(id, _, _, _)| id)
+            .collect();
+
+        rooms.choose(rng).copied()
+    }
+
+    /// Picks a random point within a room.
+    pub fn point_in(&self, room: Entity, rng: &mut impl Rng) -> Option<Vec2> {
+        let (_, room, _, parent) = self.room_q.get(room).ok()?;
+        self.map_q
+            .get(parent.parent())
+            .ok()?
+            .random_point(room, rng)
+    }
+
+    /// Picks a random point within a random room in the map with the given designation.
+    pub fn random_point(
+        &self,
+        map: Entity,
+        designation: Designation,
+        rng: &mut impl Rng,
+    ) -> Option<Vec2> {
+        let room = self.random_room(map, designation, rng)?;
+        self.point_in(room, rng)
+    }
+}
+
 pub fn room_replaced(trigger: Trigger<OnReplace, Room>, mut commands: Commands) {
     commands
         .entity(trigger.target())
@@ -41,15 +117,15 @@ pub fn update_containing_room(
     item_q: Query<
         (Entity, &Transform, Option<&ContainingRoom>),
         (
-            With<Pawn>,
+            Or<(With<Pawn>, (With<Item>, Without<HeldBy>))>,
             With<ChildOfRoot>,
             Or<(Without<ContainingRoom>, Changed<Transform>)>,
         ),
     >,
+    deterministic: Option<Res<Deterministic>>,
 ) {
-    item_q
-        .par_iter()
-        .for_each(|(id, transform, containing_room)| {
+    let update =
+        |(id, transform, containing_room): (Entity, &Transform, Option<&ContainingRoom>)| {
             let hint = containing_room.and_then(|prev_room| prev_room.hint);
             for map in &map_q {
                 if let Some((room, hint)) = map.containing_room(transform.translation.xy(), hint) {
@@ -72,7 +148,14 @@ pub fn update_containing_room(
                     commands.entity(id).remove::<ContainingRoom>();
                 });
             }
-        });
+        };
+
+    // Commands from parallel tasks are applied in whichever order the tasks finish.
+    if deterministic.is_some() {
+        item_q.iter().for_each(update);
+    } else {
+        item_q.par_iter().for_each(update);
+    }
 }
 
 impl Room {
@@ -0,0 +1,235 @@
+use std::iter;
+
+use avian2d::prelude::*;
+use bevy::{ecs::entity::EntityHashSet, prelude::*};
+use pb_util::rng::LocalRng;
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    layer::Layer,
+    map::{
+        Map,
+        door::Door,
+        perimeter::Perimeter,
+        room::{Designation, DesignationQuery},
+        wall::Wall,
+    },
+    pawn::{
+        Pawn,
+        ai::{
+            Actor,
+            interaction::{self, InteractionKind},
+            path::{PathQuery, PathTaskBundle},
+            perception::Perception,
+        },
+        health::Health,
+        role::{Guard, Prisoner},
+    },
+    security::SecurityEvent,
+};
+
+/// The chance that an idle prisoner starts an escape attempt each time escapes are planned.
+const ESCAPE_CHANCE: f64 = 0.01;
+/// The number of points on the perimeter considered when choosing an escape route.
+const ESCAPE_CANDIDATES: usize = 4;
+/// The distance from the perimeter of the points either side of it that an escape route crosses.
+const ESCAPE_MARGIN: f32 = Pawn::RADIUS * 2.;
+/// The distance from the target at which a prisoner is considered to have escaped.
+const ESCAPE_REACH: f32 = Pawn::RADIUS * 2.;
+/// The distance from a door within which a guard is considered to be guarding it.
+const GUARD_RADIUS: f32 = Pawn::VISION_RADIUS;
+
+/// Marks a prisoner attempting to escape. Escaping prisoners can climb over the perimeter.
+#[derive(Debug, Clone, Copy, Component, Serialize, Deserialize)]
+pub struct Escaping {
+    /// The point just outside the perimeter which the prisoner is heading for.
+    pub target: Vec2,
+}
+
+pub fn escaping_added(trigger: Trigger<OnInsert, Escaping>, mut commands: Commands) {
+    commands
+        .entity(trigger.target())
+        .try_insert(CollisionLayers::new(
+            Layer::Pawn,
+            LayerMask(LayerMask::ALL.0 & !Layer::Perimeter.to_bits()),
+        ));
+}
+
+pub fn escaping_removed(trigger: Trigger<OnReplace, Escaping>, mut commands: Commands) {
+    commands
+        .entity(trigger.target())
+        .try_insert(CollisionLayers::new(Layer::Pawn, LayerMask::ALL));
+}
+
+/// Has some idle prisoners start an escape attempt, choosing the route through the doors with the
+/// fewest guards nearby.
+pub fn plan(
+    mut commands: Commands,
+    prisoner_q: Query<(Entity, &Prisoner, &Actor, &Health), Without<Escaping>>,
+    guard_q: Query<&Position, With<Guard>>,
+    door_q: Query<&Wall, With<Door>>,
+    map_q: Query<&Map>,
+    perimeter_q: Query<&Perimeter>,
+    path_q: PathQuery,
+    mut security_e: EventWriter<SecurityEvent>,
+    mut rng: LocalRng,
+) {
+    for (id, prisoner, actor, health) in &prisoner_q {
+        if actor.task().is_some() || health.is_incapacitated() || !rng.random_bool(ESCAPE_CHANCE) {
+            continue;
+        }
+
+        let (Some(map), Some(position)) = (
+            path_q.map(id).and_then(|map| map_q.get(map).ok()),
+            path_q.position(id),
+        ) else {
+            continue;
+        };
+
+        let perimeter: Vec<&Perimeter> = map
+            .perimeter()
+            .filter_map(|wall| perimeter_q.get(wall.id()).ok())
+            .collect();
+        let crossings: Vec<(Vec2, Vec2)> = perimeter
+            .choose_multiple(&mut rng, ESCAPE_CANDIDATES)
+            .map(|wall| {
+                let inward = (wall.start() - wall.end()).normalize_or_zero().perp();
+                let point = wall.start().lerp(wall.end(), rng.random());
+                (
+                    point + inward * ESCAPE_MARGIN,
+                    point - inward * ESCAPE_MARGIN,
+                )
+            })
+            .collect();
+        let doors: Vec<&Wall> = map
+            .walls()
+            .filter_map(|wall| door_q.get(wall.id()).ok())
+            .collect();
+
+        // The navigation mesh ends at the perimeter, so the route is found to a point just inside
+        // it, and the prisoner then climbs over to the target. If the prisoner gets stuck the path
+        // can't be recomputed, and the attempt is abandoned.
+        let route = crossings
+            .into_iter()
+            .filter_map(|(exit, target)| {
+                let mut steps = path_q.steps(id, exit)?;
+                steps.push_back(target);
+                Some((target, steps))
+            })
+            .min_by_key(|(_, steps)| {
+                doors
+                    .iter()
+                    .filter(|door| crosses(door, iter::once(&position).chain(steps)))
+                    .map(|door| {
+                        guard_q
+                            .iter()
+                            .filter(|guard| guard.distance(door.position()) < GUARD_RADIUS)
+                            .count()
+                    })
+                    .sum::<usize>()
+            });
+        let Some((target, steps)) = route else {
+            continue;
+        };
+
+        info!("prisoner {} is attempting to escape", prisoner.number);
+        commands.spawn(PathTaskBundle::follow(id, steps, target));
+        commands.entity(id).insert(Escaping { target });
+        security_e.write(SecurityEvent::EscapeStarted {
+            prisoner: id,
+            number: prisoner.number,
+        });
+    }
+}
+
+/// Foils escape attempts seen by guards, who escort the prisoner back to a cell.
+pub fn detect(
+    mut commands: Commands,
+    guard_q: Query<(Entity, &Perception), With<Guard>>,
+    mut prisoner_q: Query<(&Prisoner, &Actor, &mut Pawn), With<Escaping>>,
+    path_q: PathQuery,
+    designation_q: DesignationQuery,
+    mut security_e: EventWriter<SecurityEvent>,
+    mut rng: LocalRng,
+) {
+    let mut spotted = EntityHashSet::default();
+    for (guard, perception) in &guard_q {
+        for visible in perception.visible() {
+            let Ok((prisoner, actor, mut pawn)) = prisoner_q.get_mut(visible) else {
+                continue;
+            };
+            if !spotted.insert(visible) {
+                continue;
+            }
+
+            info!(
+                "guard {guard} spotted prisoner {} escaping",
+                prisoner.number
+            );
+            pawn.update_movement(0., 0., 0.);
+            commands.entity(visible).remove::<Escaping>();
+
+            let cell = path_q
+                .map(visible)
+                .and_then(|map| designation_q.random_point(map, Designation::Cell, &mut rng));
+            match cell {
+                Some(destination) => {
+                    interaction::start(
+                        &mut commands,
+                        InteractionKind::Escort { destination },
+                        guard,
+                        visible,
+                    );
+                }
+                None => {
+                    if let Some(task) = actor.task() {
+                        commands.entity(task).despawn();
+                    }
+                }
+            }
+            security_e.write(SecurityEvent::EscapeSpotted {
+                prisoner: visible,
+                number: prisoner.number,
+                guard,
+            });
+        }
+    }
+}
+
+/// Removes prisoners who have climbed over the perimeter, and ends escape attempts which were
+/// abandoned.
+pub fn update(
+    mut commands: Commands,
+    prisoner_q: Query<(Entity, &Prisoner, &Actor, &Position, &Escaping)>,
+    mut security_e: EventWriter<SecurityEvent>,
+) {
+    for (id, prisoner, actor, position, escaping) in &prisoner_q {
+        if position.distance(escaping.target) < ESCAPE_REACH {
+            info!("prisoner {} escaped", prisoner.number);
+            commands.entity(id).despawn();
+            security_e.write(SecurityEvent::Escaped {
+                prisoner: id,
+                number: prisoner.number,
+            });
+        } else if actor.task().is_none() {
+            commands.entity(id).remove::<Escaping>();
+        }
+    }
+}
+
+/// Returns whether a route passes through a door.
+fn crosses<'a>(door: &Wall, route: impl IntoIterator<Item = &'a Vec2>) -> bool {
+    let inverse = door.isometry().inverse();
+    let half_length = door.length() / 2.;
+
+    let points: Vec<Vec2> = route.into_iter().map(|&point| inverse * point).collect();
+    points.windows(2).any(|segment| {
+        let [start, end] = [segment[0], segment[1]];
+        if (start.y < 0.) == (end.y < 0.) {
+            return false;
+        }
+        let x = start.x + (end.x - start.x) * start.y / (start.y - end.y);
+        x.abs() <= half_length
+    })
+}
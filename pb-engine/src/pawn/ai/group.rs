@@ -0,0 +1,217 @@
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+
+use crate::pawn::{
+    Pawn,
+    ai::{
+        Task,
+        path::{MovementQuery, PathProgress, PathQuery, PathTask, PathTaskBundle},
+        trace,
+    },
+};
+
+/// The distance between neighbouring pawns in a formation.
+const FORMATION_SPACING: f32 = Pawn::RADIUS * 3.;
+/// How far a member of a group may get ahead of the furthest member behind before waiting for it.
+const GROUP_SLACK: f32 = Pawn::RADIUS * 4.;
+/// The distance from the leader of a group within which members in the same room share its path.
+const SHARED_PATH_RADIUS: f32 = Pawn::VISION_RADIUS;
+
+/// A group of pawns moving to a destination together.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct MoveGroup {
+    pub destination: Vec2,
+}
+
+/// A path task belonging to a [`MoveGroup`].
+#[derive(Debug, Clone, Copy, Component)]
+pub struct GroupTask {
+    group: Entity,
+}
+
+impl GroupTask {
+    pub fn new(group: Entity) -> Self {
+        GroupTask { group }
+    }
+
+    pub fn group(&self) -> Entity {
+        self.group
+    }
+}
+
+/// Returns `count` points arranged in rows around `destination`, facing `direction`.
+pub fn formation(destination: Vec2, direction: Vec2, count: usize) -> Vec<Vec2> {
+    let columns = (count as f32).sqrt().ceil().max(1.) as usize;
+    let rows = count.div_ceil(columns);
+    let right = -direction.perp();
+
+    (0..count)
+        .map(|index| {
+            let row = index / columns;
+            let column = index % columns;
+            let row_len = columns.min(count - row * columns);
+
+            let x = column as f32 - (row_len - 1) as f32 / 2.;
+            let y = (rows - 1) as f32 / 2. - row as f32;
+            destination + (right * x + direction * y) * FORMATION_SPACING
+        })
+        .collect()
+}
+
+/// Spawns path tasks moving the actors into a formation at the destination, returning the group.
+///
+/// Actors near the leader of the group and in the same room follow its path, only diverging at the
+/// end to reach their place in the formation.
+pub fn move_to(
+    commands: &mut Commands,
+    path_q: &PathQuery,
+    actors: &[Entity],
+    destination: Vec2,
+) -> Option<Entity> {
+    let mut actors: Vec<(Entity, Vec2)> = actors
+        .iter()
+        .filter_map(|&actor| Some((actor, path_q.position(actor)?)))
+        .collect();
+    if actors.is_empty() {
+        return None;
+    }
+
+    let centre = actors.iter().map(|&(_, position)| position).sum::<Vec2>() / actors.len() as f32;
+    let &(leader, leader_position) = actors.iter().min_by(|(_, a), (_, b)| {
+        a.distance_squared(centre)
+            .total_cmp(&b.distance_squared(centre))
+    })?;
+    let shared = path_q.steps(leader, destination)?;
+    let leader_room = path_q.room(leader);
+
+    let approach = shared
+        .iter()
+        .rev()
+        .nth(1)
+        .copied()
+        .unwrap_or(leader_position);
+    let direction = (destination - approach).normalize_or(Vec2::Y);
+    let right = -direction.perp();
+
+    // Assign the front rows to the pawns furthest ahead, left to right, to avoid crossing paths.
+    let slots = formation(destination, direction, actors.len());
+    let columns = (actors.len() as f32).sqrt().ceil() as usize;
+    actors.sort_by(|(_, a), (_, b)| b.dot(direction).total_cmp(&a.dot(direction)));
+    for row in actors.chunks_mut(columns) {
+        row.sort_by(|(_, a), (_, b)| a.dot(right).total_cmp(&b.dot(right)));
+    }
+
+    let group = commands.spawn(MoveGroup { destination }).id();
+    for (&(actor, position), &slot) in actors.iter().zip(&slots) {
+        let slot = if path_q.steps(leader, slot).is_some() {
+            slot
+        } else {
+            destination
+        };
+
+        let steps = if position.distance(leader_position) < SHARED_PATH_RADIUS
+            && leader_room.is_some()
+            && path_q.room(actor) == leader_room
+        {
+            let mut steps = shared.clone();
+            steps.pop_back();
+            steps.push_back(slot);
+            Some(steps)
+        } else {
+            path_q.steps(actor, slot)
+        };
+
+        match steps {
+            Some(steps) => {
+                commands.spawn((
+                    PathTaskBundle::follow(actor, steps, slot),
+                    GroupTask { group },
+                ));
+            }
+            None => {
+                warn!("no path found for {actor} to {slot}");
+                trace::path_not_found(commands, actor, slot);
+            }
+        }
+    }
+
+    Some(group)
+}
+
+/// Holds back members of each group which are ahead of the rest, so that the group arrives
+/// together, and removes groups whose members have all finished.
+pub fn update(
+    mut commands: Commands,
+    group_q: Query<Entity, With<MoveGroup>>,
+    mut task_q: Query<(&Task, &PathTask, &GroupTask, &mut PathProgress)>,
+    path_q: PathQuery,
+    mut movement_q: MovementQuery,
+) -> Result {
+    let mut remaining = EntityHashMap::<f32>::default();
+    for (task, path, group_task, _) in &task_q {
+        let Some(distance) = remaining_distance(&path_q, task, path) else {
+            continue;
+        };
+        let furthest = remaining.entry(group_task.group).or_default();
+        *furthest = furthest.max(distance);
+    }
+
+    for group in &group_q {
+        if !task_q.iter().any(|(_, _, task, _)| task.group == group) {
+            commands.entity(group).despawn();
+        }
+    }
+
+    for (task, path, group_task, mut progress) in &mut task_q {
+        let (Some(distance), Some(&furthest)) = (
+            remaining_distance(&path_q, task, path),
+            remaining.get(&group_task.group),
+        ) else {
+            continue;
+        };
+
+        if distance < furthest - GROUP_SLACK {
+            movement_q.act(task.actor(), 0., 0., 0.)?;
+            progress.reset();
+        }
+    }
+
+    Ok(())
+}
+
+fn remaining_distance(path_q: &PathQuery, task: &Task, path: &PathTask) -> Option<f32> {
+    let steps = path.steps()?;
+    let mut prev = path_q.position(task.actor())?;
+    Some(
+        steps
+            .iter()
+            .map(|&step| {
+                let distance = prev.distance(step);
+                prev = step;
+                distance
+            })
+            .sum(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formation() {
+        let destination = Vec2::new(4., 2.);
+        let slots = formation(destination, Vec2::X, 5);
+        assert_eq!(slots.len(), 5);
+
+        for (i, a) in slots.iter().enumerate() {
+            for b in &slots[i + 1..] {
+                assert!(a.distance(*b) >= FORMATION_SPACING - 1e-4);
+            }
+        }
+
+        // The front row is full, and the rows are centred on the destination.
+        assert!(slots[..3].iter().all(|slot| slot.x > destination.x));
+        assert!(slots[3..].iter().all(|slot| slot.x < destination.x));
+        assert!(((slots[3].y + slots[4].y) / 2. - destination.y).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,467 @@
+use std::{collections::VecDeque, time::Duration};
+
+use avian2d::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityHashSet, MapEntities},
+        query::QueryEntityError,
+    },
+    prelude::*,
+};
+use pb_util::rng::LocalRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::pawn::{
+    Pawn,
+    ai::{
+        Actor, Task, TaskFailed, TaskFailure,
+        path::{MovementQuery, PathProgress, PathQuery},
+        perception::Perception,
+    },
+    health::Health,
+    mood::Mood,
+    role::Prisoner,
+};
+
+/// The distance between two pawns at which they can interact.
+pub const INTERACTION_RANGE: f32 = Pawn::RADIUS * 4.;
+/// The distance an escorted pawn may fall behind before its escort waits for it.
+const ESCORT_LEASH: f32 = Pawn::RADIUS * 8.;
+const CONVERSATION_DURATION: Duration = Duration::from_secs(5);
+const FIGHT_DURATION: Duration = Duration::from_secs(3);
+const TREATMENT_DURATION: Duration = Duration::from_secs(5);
+const SEARCH_DURATION: Duration = Duration::from_secs(4);
+/// The typical injury suffered by the loser of a fight. The winner is injured less.
+const FIGHT_INJURY: f32 = 0.3;
+/// The injury healed by a medic's treatment.
+const TREATMENT_HEALING: f32 = 0.5;
+/// The chance that an idle prisoner starts an interaction with a pawn it can see.
+const SOCIALISE_CHANCE: f64 = 0.1;
+/// The mood below which prisoners start fights instead of conversations.
+const FIGHT_MOOD: f32 = -0.5;
+/// The change in mood of a prisoner who wants company but finds nobody to talk to.
+const LONELY_MOOD: f32 = -0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionKind {
+    Conversation,
+    Fight,
+    /// The initiator leads the target to the destination.
+    Escort {
+        destination: Vec2,
+    },
+    /// The initiator treats the injuries of the target.
+    Treatment,
+    /// The initiator searches the target for contraband.
+    Search,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionRole {
+    Initiator,
+    Target,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractionOutcome {
+    Completed,
+    Won { winner: Entity, loser: Entity },
+}
+
+/// A task taking part in an interaction between two actors.
+///
+/// Each participant holds its own task, linked to the task of the other, and removing either task
+/// ends the interaction for both.
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
+pub struct InteractionTask {
+    kind: InteractionKind,
+    role: InteractionRole,
+    partner: Entity,
+    partner_task: Entity,
+    started: bool,
+    elapsed: Duration,
+    steps: VecDeque<Vec2>,
+    #[serde(default)]
+    progress: PathProgress,
+}
+
+#[derive(Debug, Clone, Copy, Event)]
+pub struct InteractionFinished {
+    pub kind: InteractionKind,
+    pub initiator: Entity,
+    pub target: Entity,
+    pub outcome: InteractionOutcome,
+}
+
+impl InteractionTask {
+    fn new(
+        kind: InteractionKind,
+        role: InteractionRole,
+        partner: Entity,
+        partner_task: Entity,
+    ) -> Self {
+        InteractionTask {
+            kind,
+            role,
+            partner,
+            partner_task,
+            started: false,
+            elapsed: Duration::ZERO,
+            steps: VecDeque::new(),
+            progress: PathProgress::default(),
+        }
+    }
+
+    pub fn kind(&self) -> InteractionKind {
+        self.kind
+    }
+
+    pub fn role(&self) -> InteractionRole {
+        self.role
+    }
+
+    pub fn partner(&self) -> Entity {
+        self.partner
+    }
+}
+
+impl MapEntities for InteractionTask {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.partner = entity_mapper.get_mapped(self.partner);
+        self.partner_task = entity_mapper.get_mapped(self.partner_task);
+    }
+}
+
+/// Starts an interaction between two actors, replacing their current tasks.
+pub fn start(
+    commands: &mut Commands,
+    kind: InteractionKind,
+    initiator: Entity,
+    target: Entity,
+) -> [Entity; 2] {
+    let initiator_task = commands.spawn_empty().id();
+    let target_task = commands.spawn_empty().id();
+
+    commands.entity(initiator_task).insert((
+        Task::new(initiator),
+        InteractionTask::new(kind, InteractionRole::Initiator, target, target_task),
+    ));
+    commands.entity(target_task).insert((
+        Task::new(target),
+        InteractionTask::new(kind, InteractionRole::Target, initiator, initiator_task),
+    ));
+
+    [initiator_task, target_task]
+}
+
+pub fn interaction_removed(
+    trigger: Trigger<OnReplace, InteractionTask>,
+    mut commands: Commands,
+    task_q: Query<&InteractionTask>,
+) -> Result {
+    let task = task_q.get(trigger.target())?;
+    commands.entity(task.partner_task).try_despawn();
+    Ok(())
+}
+
+/// Has some idle prisoners start a conversation, or a fight if they are unhappy, with another idle
+/// prisoner they can see. Prisoners who find nobody to talk to become unhappier. Incapacitated
+/// prisoners are left alone.
+pub fn socialise(
+    mut commands: Commands,
+    mut prisoner_q: Query<(Entity, &Actor, &Perception, &Health, &mut Mood), With<Prisoner>>,
+    mut rng: LocalRng,
+) {
+    let mut busy = EntityHashSet::default();
+    let mut lonely = Vec::new();
+    for (id, actor, perception, health, mood) in &prisoner_q {
+        if actor.task().is_some()
+            || health.is_incapacitated()
+            || busy.contains(&id)
+            || !rng.random_bool(SOCIALISE_CHANCE)
+        {
+            continue;
+        }
+
+        let Some(target) = perception.visible().find(|&other| {
+            !busy.contains(&other)
+                && prisoner_q.get(other).is_ok_and(|(_, actor, _, health, _)| {
+                    actor.task().is_none() && !health.is_incapacitated()
+                })
+        }) else {
+            lonely.push(id);
+            continue;
+        };
+
+        let kind = if mood.get() < FIGHT_MOOD {
+            InteractionKind::Fight
+        } else {
+            InteractionKind::Conversation
+        };
+        start(&mut commands, kind, id, target);
+        busy.insert(id);
+        busy.insert(target);
+    }
+
+    for id in lonely {
+        if let Ok((_, _, _, _, mut mood)) = prisoner_q.get_mut(id) {
+            mood.adjust(LONELY_MOOD);
+        }
+    }
+}
+
+pub fn update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut task_q: Query<(Entity, &Task, &mut InteractionTask)>,
+    position_q: Query<&Position>,
+    path_q: PathQuery,
+    mut movement_q: MovementQuery,
+    mut mood_q: Query<&mut Mood>,
+    mut finished_e: EventWriter<InteractionFinished>,
+    mut failed_e: EventWriter<TaskFailed>,
+    mut rng: LocalRng,
+) -> Result {
+    for (id, task, mut interaction) in &mut task_q {
+        let actor = task.actor();
+        let Ok([position, partner_position]) = position_q.get_many([actor, interaction.partner])
+        else {
+            continue;
+        };
+        let distance = position.distance(partner_position.0);
+
+        let approaching = distance > INTERACTION_RANGE
+            && match interaction.role {
+                InteractionRole::Initiator => !interaction.started,
+                InteractionRole::Target => {
+                    matches!(interaction.kind, InteractionKind::Escort { .. })
+                }
+            };
+        if approaching {
+            let failure = approach(
+                &mut interaction,
+                actor,
+                position.0,
+                partner_position.0,
+                time.delta(),
+                &path_q,
+                &mut movement_q,
+            )?;
+            if let Some(reason) = failure {
+                warn!(
+                    "{:?} between {actor} and {} failed: {reason:?}",
+                    interaction.kind, interaction.partner
+                );
+                movement_q.act(actor, 0., 0., 0.)?;
+                movement_q.act(interaction.partner, 0., 0., 0.)?;
+                commands.entity(id).despawn();
+                failed_e.write(TaskFailed {
+                    task: id,
+                    actor,
+                    reason,
+                });
+            }
+            continue;
+        }
+
+        if interaction.role == InteractionRole::Target {
+            movement_q.act(actor, 0., 0., 0.)?;
+            interaction.progress.reset();
+            continue;
+        }
+
+        if !interaction.started {
+            interaction.started = true;
+            if let InteractionKind::Escort { destination } = interaction.kind {
+                interaction.steps = path_q.steps(actor, destination).unwrap_or_default();
+            }
+        }
+
+        let finished = match interaction.kind {
+            InteractionKind::Conversation
+            | InteractionKind::Fight
+            | InteractionKind::Treatment
+            | InteractionKind::Search => {
+                movement_q.act(actor, 0., 0., 0.)?;
+                interaction.elapsed += time.delta();
+                let duration = match interaction.kind {
+                    InteractionKind::Fight => FIGHT_DURATION,
+                    InteractionKind::Treatment => TREATMENT_DURATION,
+                    InteractionKind::Search => SEARCH_DURATION,
+                    _ => CONVERSATION_DURATION,
+                };
+                interaction.elapsed >= duration
+            }
+            InteractionKind::Escort { .. } => {
+                if interaction.steps.is_empty() {
+                    true
+                } else if distance > ESCORT_LEASH {
+                    movement_q.act(actor, 0., 0., 0.)?;
+                    false
+                } else {
+                    movement_q.steer(actor, &mut interaction.steps)?;
+                    false
+                }
+            }
+        };
+        if !finished {
+            continue;
+        }
+
+        let target = interaction.partner;
+        let outcome = match interaction.kind {
+            InteractionKind::Conversation => {
+                adjust_mood(&mut mood_q, actor, 0.1);
+                adjust_mood(&mut mood_q, target, 0.1);
+                InteractionOutcome::Completed
+            }
+            InteractionKind::Fight => {
+                let (winner, loser) = if rng.random_bool(0.5) {
+                    (actor, target)
+                } else {
+                    (target, actor)
+                };
+                adjust_mood(&mut mood_q, winner, 0.05);
+                adjust_mood(&mut mood_q, loser, -0.2);
+                // Health is read by the movement query, so changes to it are deferred.
+                let injury = FIGHT_INJURY * rng.random_range(0.0..0.5);
+                commands
+                    .entity(winner)
+                    .entry::<Health>()
+                    .and_modify(move |mut health| health.injure(injury));
+                let injury = FIGHT_INJURY * rng.random_range(0.5..1.5);
+                commands
+                    .entity(loser)
+                    .entry::<Health>()
+                    .and_modify(move |mut health| health.injure(injury));
+                InteractionOutcome::Won { winner, loser }
+            }
+            InteractionKind::Escort { .. } => {
+                adjust_mood(&mut mood_q, target, -0.05);
+                InteractionOutcome::Completed
+            }
+            InteractionKind::Treatment => {
+                commands
+                    .entity(target)
+                    .entry::<Health>()
+                    .and_modify(|mut health| health.heal(TREATMENT_HEALING));
+                InteractionOutcome::Completed
+            }
+            InteractionKind::Search => {
+                adjust_mood(&mut mood_q, target, -0.05);
+                InteractionOutcome::Completed
+            }
+        };
+
+        info!(
+            "{:?} between {actor} and {target} finished",
+            interaction.kind
+        );
+        movement_q.act(actor, 0., 0., 0.)?;
+        movement_q.act(target, 0., 0., 0.)?;
+        commands.entity(id).despawn();
+        finished_e.write(InteractionFinished {
+            kind: interaction.kind,
+            initiator: actor,
+            target,
+            outcome,
+        });
+    }
+
+    Ok(())
+}
+
+/// Moves an actor along a path to its partner, re-pathing when the partner moves away from the end
+/// of the path or the actor gets stuck. Returns the reason the actor cannot reach its partner, if
+/// it has given up.
+fn approach(
+    interaction: &mut InteractionTask,
+    actor: Entity,
+    position: Vec2,
+    partner_position: Vec2,
+    delta: Duration,
+    path_q: &PathQuery,
+    movement_q: &mut MovementQuery,
+) -> Result<Option<TaskFailure>, QueryEntityError> {
+    let stuck = interaction.progress.update(position, delta);
+    if stuck && !interaction.progress.try_repath() {
+        return Ok(Some(TaskFailure::Stuck));
+    }
+
+    let moved = interaction
+        .steps
+        .back()
+        .is_none_or(|end| end.distance(partner_position) > INTERACTION_RANGE);
+    if stuck || moved {
+        match path_q.steps(actor, partner_position) {
+            Some(steps) => interaction.steps = steps,
+            None => return Ok(Some(TaskFailure::NoPath)),
+        }
+    }
+
+    movement_q.steer(actor, &mut interaction.steps)?;
+    Ok(None)
+}
+
+fn adjust_mood(mood_q: &mut Query<&mut Mood>, entity: Entity, delta: f32) {
+    if let Ok(mut mood) = mood_q.get_mut(entity) {
+        mood.adjust(delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pb_util::rng::RngSeed;
+
+    use super::*;
+
+    #[test]
+    fn test_lonely_prisoner_fights() {
+        let mut world = World::new();
+        world.insert_resource(RngSeed(0));
+
+        let prisoner = Prisoner {
+            number: 1,
+            sentence_days: 1,
+        };
+        let lonely = world
+            .spawn((
+                prisoner,
+                Actor::default(),
+                Perception::default(),
+                Health::default(),
+                Mood::default(),
+            ))
+            .id();
+
+        // With nobody to talk to, the prisoner's mood drops until it would start a fight.
+        while world.get::<Mood>(lonely).unwrap().get() >= FIGHT_MOOD {
+            world.run_system_cached(socialise).unwrap();
+        }
+        assert_eq!(world.query::<&InteractionTask>().iter(&world).count(), 0);
+
+        let other = world
+            .spawn((
+                prisoner,
+                Actor::default(),
+                Perception::default(),
+                Health::default(),
+                Mood::default(),
+            ))
+            .id();
+        world
+            .entity_mut(lonely)
+            .insert(Perception::from_iter([other]));
+
+        let kind = loop {
+            world.run_system_cached(socialise).unwrap();
+            if let Some(task) = world.query::<&InteractionTask>().iter(&world).next() {
+                break task.kind();
+            }
+        };
+        assert_eq!(kind, InteractionKind::Fight);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+
+use avian2d::prelude::*;
+use bevy::{
+    ecs::{entity::MapEntities, relationship::Relationship},
+    prelude::*,
+};
+use pb_util::rng::LocalRng;
+use rand::{Rng, seq::IteratorRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    item::{self, HeldBy, Inventory, Item},
+    map::{
+        Map,
+        room::{ContainingRoom, Room, RoomContents},
+    },
+    pawn::{
+        Pawn,
+        ai::{
+            Actor, Task,
+            escape::Escaping,
+            path::{MovementQuery, PathQuery},
+            perception::Perception,
+            reservation::{self, ReservationQuery},
+        },
+        role::{Guard, Prisoner},
+    },
+};
+
+/// The distance from an item at which a pawn can pick it up.
+const PICK_UP_RANGE: f32 = Pawn::RADIUS * 2.;
+/// The most items a prisoner will carry.
+const MAX_ITEMS: usize = 3;
+/// The chance that an idle prisoner picks up an item lying in its room.
+const SCAVENGE_CHANCE: f64 = 0.2;
+
+/// A task to walk to an item lying on the floor and pick it up.
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
+pub struct PickUpTask {
+    item: Entity,
+    steps: VecDeque<Vec2>,
+}
+
+/// A task to carry an item to a point and drop it there.
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
+pub struct DropTask {
+    item: Entity,
+    steps: VecDeque<Vec2>,
+}
+
+impl PickUpTask {
+    pub fn item(&self) -> Entity {
+        self.item
+    }
+}
+
+impl DropTask {
+    pub fn item(&self) -> Entity {
+        self.item
+    }
+}
+
+impl MapEntities for PickUpTask {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.item = entity_mapper.get_mapped(self.item);
+    }
+}
+
+impl MapEntities for DropTask {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.item = entity_mapper.get_mapped(self.item);
+    }
+}
+
+/// Reserves an item lying on the floor, and spawns a task for the actor to pick it up.
+pub fn pick_up(
+    commands: &mut Commands,
+    reservation_q: &mut ReservationQuery,
+    path_q: &PathQuery,
+    actor: Entity,
+    item: Entity,
+) -> Option<Entity> {
+    reservation::path_to_object(commands, reservation_q, path_q, actor, item, |steps, _| {
+        (Task::new(actor), PickUpTask { item, steps })
+    })
+}
+
+/// Creates a task for the actor to carry an item it holds to a point and drop it there.
+pub fn drop_at(path_q: &PathQuery, actor: Entity, item: Entity, at: Vec2) -> Option<impl Bundle> {
+    Some((
+        Task::new(actor),
+        DropTask {
+            item,
+            steps: path_q.steps(actor, at)?,
+        },
+    ))
+}
+
+/// Has some idle prisoners pick up items lying in their room, and stash contraband they are
+/// carrying somewhere else in the room when they can see a guard.
+pub fn scavenge(
+    mut commands: Commands,
+    prisoner_q: Query<
+        (
+            Entity,
+            &Actor,
+            &Perception,
+            Option<&ContainingRoom>,
+            Option<&Inventory>,
+        ),
+        (With<Prisoner>, Without<Escaping>),
+    >,
+    guard_q: Query<(), With<Guard>>,
+    room_q: Query<(&Room, &RoomContents, &ChildOf)>,
+    map_q: Query<&Map>,
+    item_q: Query<(), (With<Item>, Without<HeldBy>)>,
+    held_q: Query<&Item, With<HeldBy>>,
+    path_q: PathQuery,
+    mut reservation_q: ReservationQuery,
+    mut rng: LocalRng,
+) {
+    for (id, actor, perception, containing_room, inventory) in &prisoner_q {
+        if actor.task().is_some() {
+            continue;
+        }
+        let Some((room, contents, parent)) =
+            containing_room.and_then(|room| room_q.get(room.get()).ok())
+        else {
+            continue;
+        };
+
+        let carried = inventory.map_or(&[][..], |inventory| inventory.collection().as_slice());
+        if perception.visible().any(|pawn| guard_q.contains(pawn)) {
+            let contraband = carried
+                .iter()
+                .copied()
+                .find(|&item| held_q.get(item).is_ok_and(|item| item.contraband));
+            let stash = map_q
+                .get(parent.parent())
+                .ok()
+                .and_then(|map| map.random_point(room, &mut rng));
+            let task = contraband
+                .zip(stash)
+                .and_then(|(contraband, stash)| drop_at(&path_q, id, contraband, stash));
+            if let Some(task) = task {
+                info!("prisoner {id} is stashing contraband");
+                commands.spawn(task);
+            }
+            continue;
+        }
+
+        if carried.len() >= MAX_ITEMS || !rng.random_bool(SCAVENGE_CHANCE) {
+            continue;
+        }
+
+        let Some(item) = contents
+            .iter()
+            .filter(|&item| item_q.contains(item) && reservation_q.available(item))
+            .choose(&mut rng)
+        else {
+            continue;
+        };
+
+        pick_up(&mut commands, &mut reservation_q, &path_q, id, item);
+    }
+}
+
+pub fn update(
+    mut commands: Commands,
+    mut pick_up_q: Query<(Entity, &Task, &mut PickUpTask)>,
+    mut drop_q: Query<(Entity, &Task, &mut DropTask)>,
+    item_q: Query<(&Transform, Option<&HeldBy>), With<Item>>,
+    position_q: Query<&Position>,
+    mut movement_q: MovementQuery,
+) -> Result {
+    for (id, task, mut pick_up_task) in &mut pick_up_q {
+        let actor = task.actor();
+        let Ok((transform, None)) = item_q.get(pick_up_task.item) else {
+            // The item is gone, or someone else picked it up first.
+            movement_q.act(actor, 0., 0., 0.)?;
+            commands.entity(id).despawn();
+            continue;
+        };
+
+        if !pick_up_task.steps.is_empty() {
+            movement_q.steer(actor, &mut pick_up_task.steps)?;
+            continue;
+        }
+
+        movement_q.act(actor, 0., 0., 0.)?;
+        let position = position_q.get(actor)?;
+        if position.distance(transform.translation.xy()) <= PICK_UP_RANGE {
+            info!("{actor} picked up {}", pick_up_task.item);
+            item::pick_up(&mut commands, actor, pick_up_task.item);
+        }
+        commands.entity(id).despawn();
+    }
+
+    for (id, task, mut drop_task) in &mut drop_q {
+        let actor = task.actor();
+        if !item_q
+            .get(drop_task.item)
+            .is_ok_and(|(_, holder)| holder.is_some_and(|holder| holder.get() == actor))
+        {
+            movement_q.act(actor, 0., 0., 0.)?;
+            commands.entity(id).despawn();
+            continue;
+        }
+
+        if !drop_task.steps.is_empty() {
+            movement_q.steer(actor, &mut drop_task.steps)?;
+            continue;
+        }
+
+        movement_q.act(actor, 0., 0., 0.)?;
+        info!("{actor} dropped {}", drop_task.item);
+        item::drop_at(&mut commands, drop_task.item, position_q.get(actor)?.0);
+        commands.entity(id).despawn();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use avian2d::prelude::*;
+use bevy::{
+    ecs::{entity::EntityHashSet, relationship::Relationship},
+    prelude::*,
+};
+use pb_util::rng::LocalRng;
+
+use crate::{
+    map::room::{ContainingRoom, Designation, DesignationQuery},
+    pawn::{
+        ai::{
+            Actor,
+            interaction::{self, InteractionKind, InteractionRole, InteractionTask},
+            path::PathQuery,
+            trace,
+        },
+        health::Health,
+        role::{Job, Worker},
+    },
+};
+
+/// The injury healed each second by pawns resting in an infirmary.
+const INFIRMARY_HEALING: f32 = 0.01;
+/// The injury healed each second by pawns elsewhere.
+const NATURAL_HEALING: f32 = 0.001;
+
+/// Sends idle pawns which need treatment to an infirmary.
+pub fn seek_treatment(
+    mut commands: Commands,
+    pawn_q: Query<(Entity, &Actor, &Health, Option<&ContainingRoom>)>,
+    designation_q: DesignationQuery,
+    path_q: PathQuery,
+    mut rng: LocalRng,
+) {
+    for (id, actor, health, containing_room) in &pawn_q {
+        if actor.task().is_some() || !health.needs_treatment() || health.is_incapacitated() {
+            continue;
+        }
+
+        if containing_room.is_some_and(|room| {
+            designation_q.designation(room.get()) == Some(Designation::Infirmary)
+        }) {
+            continue;
+        }
+
+        let Some(target) = path_q
+            .map(id)
+            .and_then(|map| designation_q.random_point(map, Designation::Infirmary, &mut rng))
+        else {
+            continue;
+        };
+
+        match path_q.path(id, target) {
+            Some(path) => {
+                commands.spawn(path);
+            }
+            None => {
+                debug!("no path found for {id} to infirmary");
+                trace::path_not_found(&mut commands, id, target);
+            }
+        }
+    }
+}
+
+/// Has idle medics treat the pawn needing treatment which they can reach by the shortest path.
+pub fn assign(
+    mut commands: Commands,
+    medic_q: Query<(Entity, &Worker, &Actor, &Health)>,
+    patient_q: Query<(Entity, &Health, &Position)>,
+    interaction_q: Query<&InteractionTask>,
+    path_q: PathQuery,
+) {
+    let mut treated: EntityHashSet = interaction_q
+        .iter()
+        .filter(|task| {
+            task.kind() == InteractionKind::Treatment && task.role() == InteractionRole::Initiator
+        })
+        .map(|task| task.partner())
+        .collect();
+
+    for (medic, worker, actor, health) in &medic_q {
+        if worker.job != Job::Medic || actor.task().is_some() || health.is_incapacitated() {
+            continue;
+        }
+
+        let (Some(map), Some(position)) = (path_q.map(medic), path_q.position(medic)) else {
+            continue;
+        };
+
+        let Some((patient, _)) = patient_q
+            .iter()
+            .filter(|&(patient, health, _)| {
+                patient != medic
+                    && health.needs_treatment()
+                    && !treated.contains(&patient)
+                    && path_q.map(patient) == Some(map)
+            })
+            .filter_map(|(patient, _, patient_position)| {
+                let steps = path_q.steps(medic, patient_position.0)?;
+                let (length, _) = steps.iter().fold((0., position), |(length, prev), &step| {
+                    (length + prev.distance(step), step)
+                });
+                Some((patient, length))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        else {
+            continue;
+        };
+
+        info!("medic {medic} is treating {patient}");
+        interaction::start(&mut commands, InteractionKind::Treatment, medic, patient);
+        treated.insert(patient);
+    }
+}
+
+/// Slowly heals injured pawns, faster when they are resting in an infirmary.
+pub fn heal(
+    time: Res<Time>,
+    mut pawn_q: Query<(&mut Health, Option<&ContainingRoom>)>,
+    designation_q: DesignationQuery,
+) {
+    for (mut health, containing_room) in &mut pawn_q {
+        if !health.is_injured() {
+            continue;
+        }
+
+        let rate = if containing_room.is_some_and(|room| {
+            designation_q.designation(room.get()) == Some(Designation::Infirmary)
+        }) {
+            INFIRMARY_HEALING
+        } else {
+            NATURAL_HEALING
+        };
+        health.heal(rate * time.delta_secs());
+    }
+}
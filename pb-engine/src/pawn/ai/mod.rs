@@ -1,6 +1,20 @@
+pub mod escape;
+pub mod group;
+pub mod interaction;
+pub mod item;
+pub mod medical;
 pub mod path;
+pub mod patrol;
+pub mod perception;
+pub mod regime;
+pub mod reservation;
+pub mod search;
+pub mod trace;
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::pawn::ai::reservation::{Reservation, ReservationQuery};
 
 #[derive(Clone, Copy, Debug, Component)]
 pub struct Task {
@@ -12,6 +26,21 @@ pub struct Actor {
     task: Option<Entity>,
 }
 
+/// Sent when a task is abandoned before it could be completed.
+#[derive(Clone, Copy, Debug, Event)]
+pub struct TaskFailed {
+    pub task: Entity,
+    pub actor: Entity,
+    pub reason: TaskFailure,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskFailure {
+    NoPath,
+    Stuck,
+}
+
 impl Task {
     pub fn new(actor: Entity) -> Self {
         Task { actor }
@@ -22,6 +51,12 @@ impl Task {
     }
 }
 
+impl Actor {
+    pub fn task(&self) -> Option<Entity> {
+        self.task
+    }
+}
+
 pub fn task_added(
     trigger: Trigger<OnInsert, Task>,
     mut commands: Commands,
@@ -38,15 +73,19 @@ pub fn task_added(
 
 pub fn task_removed(
     trigger: Trigger<OnReplace, Task>,
-    task_q: Query<&Task>,
+    task_q: Query<(&Task, Option<&Reservation>)>,
     mut actor_q: Query<&mut Actor>,
+    mut reservation_q: ReservationQuery,
 ) -> Result {
-    let task = task_q.get(trigger.target())?;
+    let (task, reservation) = task_q.get(trigger.target())?;
     if let Ok(mut actor) = actor_q.get_mut(task.actor) {
         if actor.task == Some(trigger.target()) {
             actor.task = None;
         }
     }
+    if let Some(reservation) = reservation {
+        reservation_q.release(reservation, trigger.target());
+    }
     Ok(())
 }
 
@@ -0,0 +1,259 @@
+//! Local collision avoidance between pawns, using optimal reciprocal collision avoidance (ORCA).
+//!
+//! See <https://gamma.cs.unc.edu/ORCA/> for a description of the algorithm.
+
+use bevy::prelude::*;
+
+const EPSILON: f32 = 1e-5;
+
+/// The time window over which collisions with other pawns are avoided.
+pub const TIME_HORIZON: f32 = 2.;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Agent {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    point: Vec2,
+    direction: Vec2,
+}
+
+/// Computes the velocity closest to `preferred` which avoids collisions with `neighbours` within
+/// [`TIME_HORIZON`], assuming they take an equal share of the responsibility for avoiding it.
+pub fn velocity(
+    agent: &Agent,
+    preferred: Vec2,
+    max_speed: f32,
+    time_step: f32,
+    neighbours: impl IntoIterator<Item = Agent>,
+) -> Vec2 {
+    let lines: Vec<Line> = neighbours
+        .into_iter()
+        .map(|other| orca_line(agent, &other, time_step))
+        .collect();
+
+    let mut result = Vec2::ZERO;
+    let failed = linear_program2(&lines, max_speed, preferred, false, &mut result);
+    if failed < lines.len() {
+        linear_program3(&lines, failed, max_speed, &mut result);
+    }
+    result
+}
+
+fn orca_line(agent: &Agent, other: &Agent, time_step: f32) -> Line {
+    let relative_position = other.position - agent.position;
+    let relative_velocity = agent.velocity - other.velocity;
+    let dist_sq = relative_position.length_squared();
+    let combined_radius = agent.radius + other.radius;
+    let combined_radius_sq = combined_radius * combined_radius;
+
+    let (direction, u) = if dist_sq > combined_radius_sq {
+        let w = relative_velocity - relative_position / TIME_HORIZON;
+        let w_length_sq = w.length_squared();
+        let dot = w.dot(relative_position);
+
+        if dot < 0. && dot * dot > combined_radius_sq * w_length_sq {
+            // Project on the cut-off circle.
+            let w_length = w_length_sq.sqrt();
+            let unit_w = w / w_length;
+            (
+                Vec2::new(unit_w.y, -unit_w.x),
+                (combined_radius / TIME_HORIZON - w_length) * unit_w,
+            )
+        } else {
+            // Project on the nearest leg of the velocity obstacle.
+            let leg = (dist_sq - combined_radius_sq).sqrt();
+            let direction = if relative_position.perp_dot(w) > 0. {
+                Vec2::new(
+                    relative_position.x * leg - relative_position.y * combined_radius,
+                    relative_position.x * combined_radius + relative_position.y * leg,
+                ) / dist_sq
+            } else {
+                -Vec2::new(
+                    relative_position.x * leg + relative_position.y * combined_radius,
+                    -relative_position.x * combined_radius + relative_position.y * leg,
+                ) / dist_sq
+            };
+            (
+                direction,
+                relative_velocity.dot(direction) * direction - relative_velocity,
+            )
+        }
+    } else {
+        // Already colliding, so resolve the collision within the next time step.
+        let w = relative_velocity - relative_position / time_step;
+        let w_length = w.length();
+        let unit_w = w.normalize_or(Vec2::X);
+        (
+            Vec2::new(unit_w.y, -unit_w.x),
+            (combined_radius / time_step - w_length) * unit_w,
+        )
+    };
+
+    Line {
+        point: agent.velocity + 0.5 * u,
+        direction,
+    }
+}
+
+fn linear_program1(
+    lines: &[Line],
+    line_no: usize,
+    radius: f32,
+    optimal: Vec2,
+    direction_opt: bool,
+    result: &mut Vec2,
+) -> bool {
+    let line = lines[line_no];
+    let dot = line.point.dot(line.direction);
+    let discriminant = dot * dot + radius * radius - line.point.length_squared();
+    if discriminant < 0. {
+        return false;
+    }
+
+    let sqrt_discriminant = discriminant.sqrt();
+    let mut t_left = -dot - sqrt_discriminant;
+    let mut t_right = -dot + sqrt_discriminant;
+
+    for other in &lines[..line_no] {
+        let denominator = line.direction.perp_dot(other.direction);
+        let numerator = other.direction.perp_dot(line.point - other.point);
+
+        if denominator.abs() <= EPSILON {
+            if numerator < 0. {
+                return false;
+            }
+            continue;
+        }
+
+        let t = numerator / denominator;
+        if denominator >= 0. {
+            t_right = t_right.min(t);
+        } else {
+            t_left = t_left.max(t);
+        }
+
+        if t_left > t_right {
+            return false;
+        }
+    }
+
+    *result = if direction_opt {
+        if optimal.dot(line.direction) > 0. {
+            line.point + t_right * line.direction
+        } else {
+            line.point + t_left * line.direction
+        }
+    } else {
+        let t = line.direction.dot(optimal - line.point);
+        line.point + t.max(t_left).min(t_right) * line.direction
+    };
+    true
+}
+
+fn linear_program2(
+    lines: &[Line],
+    radius: f32,
+    optimal: Vec2,
+    direction_opt: bool,
+    result: &mut Vec2,
+) -> usize {
+    *result = if direction_opt {
+        optimal * radius
+    } else {
+        optimal.clamp_length_max(radius)
+    };
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.direction.perp_dot(line.point - *result) > 0. {
+            let prev = *result;
+            if !linear_program1(lines, i, radius, optimal, direction_opt, result) {
+                *result = prev;
+                return i;
+            }
+        }
+    }
+
+    lines.len()
+}
+
+fn linear_program3(lines: &[Line], begin: usize, radius: f32, result: &mut Vec2) {
+    let mut distance = 0.;
+
+    for (i, line) in lines.iter().enumerate().skip(begin) {
+        if line.direction.perp_dot(line.point - *result) <= distance {
+            continue;
+        }
+
+        let projected: Vec<Line> = lines[..i]
+            .iter()
+            .filter_map(|other| {
+                let determinant = line.direction.perp_dot(other.direction);
+                let point = if determinant.abs() <= EPSILON {
+                    if line.direction.dot(other.direction) > 0. {
+                        return None;
+                    }
+                    0.5 * (line.point + other.point)
+                } else {
+                    line.point
+                        + (other.direction.perp_dot(line.point - other.point) / determinant)
+                            * line.direction
+                };
+
+                Some(Line {
+                    point,
+                    direction: (other.direction - line.direction).normalize_or_zero(),
+                })
+            })
+            .collect();
+
+        let prev = *result;
+        let optimal = Vec2::new(-line.direction.y, line.direction.x);
+        if linear_program2(&projected, radius, optimal, true, result) < projected.len() {
+            *result = prev;
+        }
+
+        distance = line.direction.perp_dot(line.point - *result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_neighbours() {
+        let agent = Agent {
+            position: Vec2::ZERO,
+            velocity: Vec2::X,
+            radius: 0.5,
+        };
+
+        let result = velocity(&agent, Vec2::new(1., 1.), 1.5, 1. / 64., []);
+        assert_eq!(result, Vec2::new(1., 1.));
+    }
+
+    #[test]
+    fn test_head_on() {
+        let agent = Agent {
+            position: Vec2::ZERO,
+            velocity: Vec2::X,
+            radius: 0.5,
+        };
+        let other = Agent {
+            position: Vec2::new(3., 0.),
+            velocity: -Vec2::X,
+            radius: 0.5,
+        };
+
+        let result = velocity(&agent, Vec2::X, 1.5, 1. / 64., [other]);
+        assert!(
+            result.y.abs() > 0.1,
+            "expected to steer aside, got {result}"
+        );
+    }
+}
@@ -1,35 +1,64 @@
+pub mod avoidance;
 #[rustfmt::skip]
 mod model;
 
-use std::{collections::VecDeque, f32::consts::PI};
+use std::{collections::VecDeque, f32::consts::PI, time::Duration};
 
 use avian2d::{collision::collider::contact_query, prelude::*};
 use bevy::{
     ecs::{query::QueryEntityError, relationship::Relationship, system::SystemParam},
     prelude::*,
 };
+use serde::{Deserialize, Serialize};
 use tokio::sync::oneshot;
 
 use crate::{
     layer::Layer,
     map::{mesh::MapMesh, room::ContainingRoom, wall::Wall},
-    pawn::{Pawn, ai::Task},
+    pawn::{
+        MovementStats, Pawn,
+        ai::{
+            Task, TaskFailed, TaskFailure,
+            trace::{self, BehaviourTrace, TraceEvent},
+        },
+        health::Health,
+    },
 };
 
-const POSITION_EPSILON: f32 = Pawn::MAX_VELOCITY / 64.;
+const POSITION_EPSILON: f32 = MovementStats::DEFAULT.max_velocity / 64.;
+
+/// The minimum distance a pawn must move within [`STUCK_TIMEOUT`] to be considered making progress.
+const STUCK_DISTANCE: f32 = Pawn::RADIUS;
+const STUCK_TIMEOUT: Duration = Duration::from_secs(3);
+/// The number of times a stuck pawn is re-pathed before its task is failed.
+const MAX_REPATH_ATTEMPTS: u32 = 3;
 
 #[derive(Bundle)]
 pub struct PathTaskBundle {
     task: Task,
     path: PathTask,
+    target: PathTarget,
 }
 
 #[derive(Debug, Component)]
+#[require(PathProgress)]
 pub enum PathTask {
     Pending(oneshot::Receiver<Option<VecDeque<Vec2>>>),
     Running(VecDeque<Vec2>),
 }
 
+/// The final destination of a path task, used to recompute the path if it becomes invalid.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct PathTarget(pub Vec2);
+
+/// Tracks whether the actor of a path task is making progress towards its target.
+#[derive(Debug, Default, Clone, Component, Serialize, Deserialize)]
+pub struct PathProgress {
+    position: Option<Vec2>,
+    elapsed: Duration,
+    repaths: u32,
+}
+
 #[derive(SystemParam)]
 pub struct MovementQuery<'w, 's> {
     spatial_query: SpatialQuery<'w, 's>,
@@ -38,6 +67,8 @@ pub struct MovementQuery<'w, 's> {
         's,
         (
             &'static mut Pawn,
+            &'static MovementStats,
+            &'static Health,
             &'static Position,
             &'static Rotation,
             &'static Collider,
@@ -59,6 +90,7 @@ pub struct MovementQuery<'w, 's> {
         ),
     >,
     config: Res<'w, PathQueryConfig>,
+    fixed_time: Res<'w, Time<Fixed>>,
 }
 
 #[derive(SystemParam)]
@@ -70,9 +102,12 @@ pub struct PathQuery<'w, 's> {
 
 #[derive(Resource)]
 pub struct PathQueryConfig {
+    /// Whether to steer around nearby pawns using [`avoidance`].
+    pub avoidance: bool,
     collider: Collider,
     all_filter: SpatialQueryFilter,
     wall_filter: SpatialQueryFilter,
+    pawn_filter: SpatialQueryFilter,
 }
 
 #[derive(Debug)]
@@ -96,6 +131,103 @@ impl PathTaskBundle {
         PathTaskBundle {
             task: Task::new(actor),
             path: PathTask::Running(VecDeque::from_iter([to])),
+            target: PathTarget(to),
+        }
+    }
+
+    /// Follows precomputed steps, which should end at the target.
+    pub fn follow(actor: Entity, steps: VecDeque<Vec2>, to: Vec2) -> Self {
+        PathTaskBundle {
+            task: Task::new(actor),
+            path: PathTask::Running(steps),
+            target: PathTarget(to),
+        }
+    }
+
+    pub fn steps(&self) -> Option<&VecDeque<Vec2>> {
+        self.path.steps()
+    }
+}
+
+pub fn repath(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut task_q: Query<(Entity, &Task, &mut PathTask, &PathTarget, &mut PathProgress)>,
+    mut pawn_q: Query<&mut Pawn>,
+    changed_mesh_q: Query<(), Changed<MapMesh>>,
+    path_q: PathQuery,
+    mut failed_e: EventWriter<TaskFailed>,
+    mut trace_q: Query<&mut BehaviourTrace>,
+) {
+    for (id, task, mut path, target, mut progress) in &mut task_q {
+        if path.steps().is_none() {
+            continue;
+        }
+
+        let Some(position) = path_q.position(task.actor) else {
+            continue;
+        };
+
+        let invalidated = path_q
+            .map(task.actor)
+            .is_some_and(|map| changed_mesh_q.contains(map));
+        let stuck = progress.update(position, time.delta());
+        if !invalidated && !stuck {
+            continue;
+        }
+
+        if stuck {
+            trace::record(
+                &mut trace_q,
+                time.elapsed(),
+                task.actor,
+                TraceEvent::Stuck {
+                    repaths: progress.repaths,
+                },
+            );
+        }
+
+        let failure = if stuck && !progress.try_repath() {
+            Some(TaskFailure::Stuck)
+        } else if let Some(steps) = path_q.steps(task.actor, target.0) {
+            if stuck {
+                info!("re-pathing stuck pawn {}", task.actor);
+            } else {
+                info!("re-pathing {} after map change", task.actor);
+            }
+
+            trace::record(
+                &mut trace_q,
+                time.elapsed(),
+                task.actor,
+                TraceEvent::PathFound {
+                    target: target.0,
+                    steps: steps.len(),
+                },
+            );
+            *path = PathTask::Running(steps);
+            None
+        } else {
+            trace::record(
+                &mut trace_q,
+                time.elapsed(),
+                task.actor,
+                TraceEvent::PathNotFound { target: target.0 },
+            );
+            Some(TaskFailure::NoPath)
+        };
+
+        if let Some(reason) = failure {
+            warn!("path task for {} failed: {reason:?}", task.actor);
+            if let Ok(mut pawn) = pawn_q.get_mut(task.actor) {
+                pawn.update_movement(0., 0., 0.);
+            }
+            failed_e.write(TaskFailed {
+                task: id,
+                actor: task.actor,
+                reason,
+            });
+            commands.entity(id).despawn();
         }
     }
 }
@@ -107,20 +239,17 @@ pub fn update(
 ) -> Result {
     for (id, task, mut path) in &mut task_q {
         let Some(steps) = path.poll() else {
-            return Ok(());
+            continue;
         };
 
         if steps.is_empty() {
             info!("completed path");
             path_q.act(task.actor, 0., 0., 0.)?;
             commands.entity(id).despawn();
-            return Ok(());
+            continue;
         }
 
-        let obs = path_q.observe(task.actor, steps)?;
-
-        let [[angle, force, torque, _, _, _]] = model::main_graph([obs.into()]);
-        path_q.act(task.actor, angle, force, torque)?;
+        path_q.steer(task.actor, steps)?;
     }
 
     Ok(())
@@ -128,16 +257,33 @@ pub fn update(
 
 impl PathQuery<'_, '_> {
     pub fn path(&self, entity: Entity, to: Vec2) -> Option<PathTaskBundle> {
-        let (pos, containing_room) = self.pawn_q.get(entity).ok()?;
-        let map = self.parent_q.get(containing_room.get()).ok()?.parent();
-        let room = self.mesh_q.get(map).ok()?;
-        if let Some(path) = room.path(pos.0, to) {
-            return Some(PathTaskBundle {
-                task: Task::new(entity),
-                path: PathTask::Running(path.path.into_iter().collect()),
-            });
-        }
-        None
+        Some(PathTaskBundle {
+            task: Task::new(entity),
+            path: PathTask::Running(self.steps(entity, to)?),
+            target: PathTarget(to),
+        })
+    }
+
+    pub fn steps(&self, entity: Entity, to: Vec2) -> Option<VecDeque<Vec2>> {
+        let (pos, _) = self.pawn_q.get(entity).ok()?;
+        let mesh = self.mesh_q.get(self.map(entity)?).ok()?;
+        let path = mesh.path(pos.0, to)?;
+        Some(path.path.into_iter().collect())
+    }
+
+    pub fn map(&self, entity: Entity) -> Option<Entity> {
+        let (_, containing_room) = self.pawn_q.get(entity).ok()?;
+        Some(self.parent_q.get(containing_room.get()).ok()?.parent())
+    }
+
+    pub fn position(&self, entity: Entity) -> Option<Vec2> {
+        let (pos, _) = self.pawn_q.get(entity).ok()?;
+        Some(pos.0)
+    }
+
+    pub fn room(&self, entity: Entity) -> Option<Entity> {
+        let (_, containing_room) = self.pawn_q.get(entity).ok()?;
+        Some(containing_room.get())
     }
 }
 
@@ -147,8 +293,9 @@ impl MovementQuery<'_, '_> {
         entity: Entity,
         steps: &mut VecDeque<Vec2>,
     ) -> Result<PathObservation, QueryEntityError> {
-        let (_, position, rotation, collider, linear_velocity, angular_velocity) =
+        let (_, stats, health, position, rotation, collider, linear_velocity, angular_velocity) =
             self.pawn_q.get(entity)?;
+        let stats = stats.limited(health);
 
         let target = loop {
             let Some(&current_step) = steps.front() else {
@@ -170,18 +317,42 @@ impl MovementQuery<'_, '_> {
             break Some(current_step);
         };
 
-        let collision = self.collision(entity, *position, *rotation, collider);
+        let collision = self.collision(entity, *position, *rotation, collider, &stats);
+
+        let mut target = target.unwrap_or(position.0);
+        if self.config.avoidance {
+            target = self.avoid(
+                entity,
+                position.0,
+                linear_velocity.0,
+                stats.max_velocity,
+                target,
+            );
+        }
 
         Ok(PathObservation::new(
+            &stats,
             position,
             rotation,
             linear_velocity,
             angular_velocity,
             collision,
-            target.unwrap_or(position.0),
+            target,
         ))
     }
 
+    /// Moves the pawn towards the first visible step using the movement policy.
+    pub fn steer(
+        &mut self,
+        entity: Entity,
+        steps: &mut VecDeque<Vec2>,
+    ) -> Result<(), QueryEntityError> {
+        let obs = self.observe(entity, steps)?;
+
+        let [[angle, force, torque, _, _, _]] = model::main_graph([obs.into()]);
+        self.act(entity, angle, force, torque)
+    }
+
     pub fn act(
         &mut self,
         entity: Entity,
@@ -189,7 +360,7 @@ impl MovementQuery<'_, '_> {
         force: f32,
         torque: f32,
     ) -> Result<(), QueryEntityError> {
-        let (mut pawn, _, _, _, _, _) = self.pawn_q.get_mut(entity)?;
+        let (mut pawn, _, _, _, _, _, _, _) = self.pawn_q.get_mut(entity)?;
         pawn.update_movement(angle, force, torque);
         Ok(())
     }
@@ -200,7 +371,9 @@ impl MovementQuery<'_, '_> {
         pawn_position: Position,
         pawn_rotation: Rotation,
         pawn_collider: &Collider,
+        pawn_stats: &MovementStats,
     ) -> Option<PathCollision> {
+        let max_velocity = pawn_stats.max_velocity.max(f32::EPSILON);
         let inv_isometry = Isometry2d::new(pawn_position.0, pawn_rotation.into()).inverse();
 
         let mut result = None;
@@ -249,7 +422,7 @@ impl MovementQuery<'_, '_> {
                         normal: normal.to_angle(),
                         velocity_t: pawn_space_velocity.to_angle(),
                         velocity_r: pawn_space_velocity.length_squared()
-                            / (Pawn::MAX_VELOCITY * Pawn::MAX_VELOCITY),
+                            / (max_velocity * max_velocity),
                         is_pawn: collider_is_pawn,
                         is_wall: collider_is_wall,
                     };
@@ -270,6 +443,66 @@ impl MovementQuery<'_, '_> {
         result
     }
 
+    /// Redirects the target so the pawn heads in a direction which avoids nearby pawns, keeping the
+    /// same distance so the observation is otherwise unchanged.
+    fn avoid(
+        &self,
+        entity: Entity,
+        position: Vec2,
+        velocity: Vec2,
+        max_velocity: f32,
+        target: Vec2,
+    ) -> Vec2 {
+        let delta = target - position;
+        let distance = delta.length();
+        if distance < POSITION_EPSILON {
+            return target;
+        }
+
+        let mut neighbours = Vec::new();
+        self.spatial_query.shape_intersections_callback(
+            &self.config.collider,
+            position,
+            0.,
+            &self.config.pawn_filter,
+            |neighbour| {
+                if neighbour == entity {
+                    return true;
+                }
+
+                if let Ok((neighbour_position, _, _, neighbour_velocity, _, _)) =
+                    self.collider_q.get(neighbour)
+                {
+                    neighbours.push(avoidance::Agent {
+                        position: neighbour_position.0,
+                        velocity: neighbour_velocity.0,
+                        radius: Pawn::RADIUS,
+                    });
+                }
+                true
+            },
+        );
+        if neighbours.is_empty() {
+            return target;
+        }
+
+        let agent = avoidance::Agent {
+            position,
+            velocity,
+            radius: Pawn::RADIUS,
+        };
+        let preferred = delta.clamp_length_max(max_velocity);
+        let velocity = avoidance::velocity(
+            &agent,
+            preferred,
+            max_velocity,
+            self.fixed_time.timestep().as_secs_f32(),
+            neighbours,
+        );
+
+        position + velocity.normalize_or_zero() * distance
+    }
+
     fn visible(&self, position: Vec2, target: Vec2) -> bool {
         let delta = target - position;
         let Ok(dir) = Dir2::new(delta) else {
@@ -291,6 +524,7 @@ impl MovementQuery<'_, '_> {
 impl Default for PathQueryConfig {
     fn default() -> Self {
         Self {
+            avoidance: true,
             collider: Collider::circle(Pawn::VISION_RADIUS),
             all_filter: SpatialQueryFilter {
                 mask: LayerMask(Layer::Wall.to_bits() | Layer::Perimeter.to_bits()),
@@ -300,6 +534,10 @@ impl Default for PathQueryConfig {
                 mask: Layer::Wall.into(),
                 ..Default::default()
             },
+            pawn_filter: SpatialQueryFilter {
+                mask: Layer::Pawn.into(),
+                ..Default::default()
+            },
         }
     }
 }
@@ -333,6 +571,7 @@ impl PathObservation {
     pub const SIZE: usize = 12;
 
     pub fn new(
+        stats: &MovementStats,
         position: &Position,
         rotation: &Rotation,
         linear_velocity: &LinearVelocity,
@@ -347,11 +586,16 @@ impl PathObservation {
 
         let collision = collision.unwrap_or_default();
 
+        // Normalise by the pawn's own limits, so that a slow pawn moving at its top speed observes the
+        // same as a fast one.
+        let max_velocity = stats.max_velocity.max(f32::EPSILON);
+        let max_angular_velocity = stats.max_angular_velocity.max(f32::EPSILON);
+
         PathObservation {
             linear_velocity_t: pawn_space_linear_velocity.to_angle() / PI,
             linear_velocity_r: pawn_space_linear_velocity.length_squared()
-                / (Pawn::MAX_VELOCITY * Pawn::MAX_VELOCITY),
-            angular_velocity: angular_velocity.0 / Pawn::MAX_ANGULAR_VELOCITY,
+                / (max_velocity * max_velocity),
+            angular_velocity: angular_velocity.0 / max_angular_velocity,
             target_t: pawn_space_target.to_angle() / PI,
             target_r: pawn_space_target.length().min(Pawn::VISION_RADIUS),
             collision_t: collision.angle / PI,
@@ -425,3 +669,63 @@ impl PathTask {
         }
     }
 }
+
+impl PathProgress {
+    /// Restarts the stuck timeout, for actors which are deliberately not moving.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Records the actor's current position, returning `true` if it has not moved for [`STUCK_TIMEOUT`].
+    pub(crate) fn update(&mut self, position: Vec2, delta: Duration) -> bool {
+        match self.position {
+            Some(prev) if prev.distance_squared(position) < STUCK_DISTANCE * STUCK_DISTANCE => {
+                self.elapsed += delta;
+                if self.elapsed >= STUCK_TIMEOUT {
+                    self.elapsed = Duration::ZERO;
+                    return true;
+                }
+            }
+            Some(_) => {
+                self.position = Some(position);
+                self.elapsed = Duration::ZERO;
+                self.repaths = 0;
+            }
+            None => self.position = Some(position),
+        }
+
+        false
+    }
+
+    /// Counts an attempt to re-path a stuck actor, returning `false` if it has run out of attempts.
+    pub(crate) fn try_repath(&mut self) -> bool {
+        if self.repaths >= MAX_REPATH_ATTEMPTS {
+            return false;
+        }
+        self.repaths += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pawn::PawnBundle;
+
+    #[test]
+    fn test_update_all_tasks() {
+        let mut world = World::new();
+        world.init_resource::<SpatialQueryPipeline>();
+        world.init_resource::<PathQueryConfig>();
+        world.init_resource::<Time<Fixed>>();
+
+        // A finished task shouldn't stop the tasks after it from being updated.
+        for x in [0., 1.] {
+            let actor = world.spawn(PawnBundle::new(Vec2::new(x, 0.), 0.)).id();
+            world.spawn((Task::new(actor), PathTask::Running(VecDeque::new())));
+        }
+
+        world.run_system_cached(update).unwrap().unwrap();
+        assert_eq!(world.query::<&PathTask>().iter(&world).count(), 0);
+    }
+}
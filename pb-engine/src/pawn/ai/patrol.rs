@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::entity::{EntityHashMap, MapEntities},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    map::patrol::PatrolRoute,
+    pawn::{
+        ai::{Actor, path::PathQuery, trace},
+        role::Guard,
+    },
+};
+
+/// Tracks a guard's progress around its assigned patrol route.
+#[derive(Debug, Clone, Copy, Component, Serialize, Deserialize)]
+pub struct Patrolling {
+    route: Entity,
+    next: usize,
+    state: PatrolState,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PatrolState {
+    Moving,
+    Waiting(Duration),
+}
+
+impl Patrolling {
+    pub fn route(&self) -> Entity {
+        self.route
+    }
+}
+
+impl MapEntities for Patrolling {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.route = entity_mapper.get_mapped(self.route);
+    }
+}
+
+/// Assigns guards without a patrol to the route in their map with the fewest guards.
+pub fn assign(
+    mut commands: Commands,
+    guard_q: Query<Entity, (With<Guard>, Without<Patrolling>)>,
+    patrolling_q: Query<&Patrolling>,
+    route_q: Query<(Entity, &PatrolRoute, &ChildOf)>,
+    path_q: PathQuery,
+) {
+    let mut counts = EntityHashMap::<usize>::default();
+    for patrol in &patrolling_q {
+        *counts.entry(patrol.route).or_default() += 1;
+    }
+
+    for id in &guard_q {
+        let Some(map) = path_q.map(id) else {
+            continue;
+        };
+
+        let route = route_q
+            .iter()
+            .filter(|(_, route, parent)| !route.is_empty() && parent.parent() == map)
+            .map(|(route, _, _)| route)
+            .min_by_key(|route| counts.get(route).copied().unwrap_or_default());
+        if let Some(route) = route {
+            *counts.entry(route).or_default() += 1;
+            commands.entity(id).insert(Patrolling {
+                route,
+                next: 0,
+                state: PatrolState::Waiting(Duration::ZERO),
+            });
+        }
+    }
+}
+
+/// Moves idle guards to the next waypoint of their route once they have waited at the last one.
+pub fn update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut guard_q: Query<(Entity, &Actor, &mut Patrolling)>,
+    route_q: Query<&PatrolRoute>,
+    path_q: PathQuery,
+) {
+    for (id, actor, mut patrol) in &mut guard_q {
+        let Ok(route) = route_q.get(patrol.route) else {
+            commands.entity(id).remove::<Patrolling>();
+            continue;
+        };
+        if actor.task().is_some() {
+            continue;
+        }
+
+        match patrol.state {
+            PatrolState::Moving => {
+                let pause = route
+                    .waypoint(patrol.next)
+                    .map_or(Duration::ZERO, |waypoint| waypoint.pause);
+                patrol.next = (patrol.next + 1) % route.len().max(1);
+                patrol.state = PatrolState::Waiting(pause);
+            }
+            PatrolState::Waiting(remaining) if remaining > time.delta() => {
+                patrol.state = PatrolState::Waiting(remaining - time.delta());
+            }
+            PatrolState::Waiting(_) => {
+                let Some(waypoint) = route.waypoint(patrol.next) else {
+                    continue;
+                };
+
+                match path_q.path(id, waypoint.position) {
+                    Some(path) => {
+                        commands.spawn(path);
+                        patrol.state = PatrolState::Moving;
+                    }
+                    None => {
+                        debug!("no path found for {id} to patrol waypoint");
+                        trace::path_not_found(&mut commands, id, waypoint.position);
+                        patrol.next = (patrol.next + 1) % route.len();
+                        patrol.state = PatrolState::Waiting(waypoint.pause);
+                    }
+                }
+            }
+        }
+    }
+}
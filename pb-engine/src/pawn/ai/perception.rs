@@ -0,0 +1,113 @@
+use std::{f32::consts::PI, time::Duration};
+
+use avian2d::prelude::*;
+use bevy::{ecs::entity::EntityHashSet, prelude::*};
+
+use crate::{determinism::Deterministic, layer::Layer, pawn::Pawn};
+
+/// Half of the angle of a pawn's field of view, either side of its facing direction.
+pub const VISION_HALF_ANGLE: f32 = PI / 3.;
+/// The interval between updates to [`Perception`].
+pub const PERCEPTION_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The set of pawns currently visible to a pawn.
+#[derive(Debug, Default, Clone, PartialEq, Component)]
+pub struct Perception {
+    visible: EntityHashSet,
+}
+
+#[derive(Resource)]
+pub struct PerceptionConfig {
+    collider: Collider,
+    pawn_filter: SpatialQueryFilter,
+    wall_filter: SpatialQueryFilter,
+}
+
+impl Perception {
+    pub fn can_see(&self, entity: Entity) -> bool {
+        self.visible.contains(&entity)
+    }
+
+    pub fn visible(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.visible.iter().copied()
+    }
+}
+
+impl FromIterator<Entity> for Perception {
+    fn from_iter<T: IntoIterator<Item = Entity>>(iter: T) -> Self {
+        Perception {
+            visible: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Default for PerceptionConfig {
+    fn default() -> Self {
+        Self {
+            collider: Collider::circle(Pawn::VISION_RADIUS),
+            pawn_filter: SpatialQueryFilter {
+                mask: Layer::Pawn.into(),
+                ..Default::default()
+            },
+            wall_filter: SpatialQueryFilter {
+                mask: Layer::Wall.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+pub fn update(
+    spatial_query: SpatialQuery,
+    config: Res<PerceptionConfig>,
+    mut pawn_q: Query<(Entity, &Position, &Rotation, &mut Perception)>,
+    position_q: Query<&Position, With<Pawn>>,
+    deterministic: Option<Res<Deterministic>>,
+) {
+    let update = |(id, position, rotation, mut perception): (
+        Entity,
+        &Position,
+        &Rotation,
+        Mut<Perception>,
+    )| {
+        let forward = *rotation * Vec2::X;
+
+        let mut visible = EntityHashSet::default();
+        for target in
+            spatial_query.shape_intersections(&config.collider, position.0, 0., &config.pawn_filter)
+        {
+            if target == id {
+                continue;
+            }
+            let Ok(target_position) = position_q.get(target) else {
+                continue;
+            };
+
+            let delta = target_position.0 - position.0;
+            let Ok(dir) = Dir2::new(delta) else {
+                visible.insert(target);
+                continue;
+            };
+            if forward.angle_to(*dir).abs() > VISION_HALF_ANGLE {
+                continue;
+            }
+
+            let occluded = spatial_query
+                .cast_ray(position.0, dir, delta.length(), true, &config.wall_filter)
+                .is_some();
+            if !occluded {
+                visible.insert(target);
+            }
+        }
+
+        if perception.visible != visible {
+            perception.visible = visible;
+        }
+    };
+
+    if deterministic.is_some() {
+        pawn_q.iter_mut().for_each(update);
+    } else {
+        pawn_q.par_iter_mut().for_each(update);
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::{ecs::relationship::Relationship, prelude::*};
+use pb_util::rng::LocalRng;
+
+use crate::{
+    clock::Clock,
+    map::room::{ContainingRoom, DesignationQuery},
+    pawn::{
+        ai::{Actor, path::PathQuery, trace},
+        health::Health,
+        role::Prisoner,
+    },
+    regime::Regime,
+};
+
+/// Sends idle prisoners to a room matching the current activity of the regime, unless they need
+/// treatment.
+pub fn update(
+    mut commands: Commands,
+    clock: Res<Clock>,
+    regime: Res<Regime>,
+    prisoner_q: Query<(Entity, &Actor, &Health, Option<&ContainingRoom>), With<Prisoner>>,
+    designation_q: DesignationQuery,
+    path_q: PathQuery,
+    mut rng: LocalRng,
+) {
+    let designation = regime.current(&clock).designation();
+
+    for (id, actor, health, containing_room) in &prisoner_q {
+        if actor.task().is_some() || health.needs_treatment() {
+            continue;
+        }
+
+        if containing_room
+            .is_some_and(|room| designation_q.designation(room.get()) == Some(designation))
+        {
+            continue;
+        }
+
+        let Some(target) = path_q
+            .map(id)
+            .and_then(|map| designation_q.random_point(map, designation, &mut rng))
+        else {
+            continue;
+        };
+
+        match path_q.path(id, target) {
+            Some(path) => {
+                commands.spawn(path);
+            }
+            None => {
+                debug!("no path found for {id} to {designation:?}");
+                trace::path_not_found(&mut commands, id, target);
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    ecs::{entity::MapEntities, system::SystemParam},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::pawn::ai::{path::PathQuery, trace};
+
+/// The places around an object where pawns can stand to use it.
+#[derive(Debug, Default, Clone, Component)]
+pub struct UsageSlots {
+    slots: Vec<UsageSlot>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UsageSlot {
+    /// The point a pawn stands at to use the object, relative to the object.
+    pub offset: Vec2,
+    /// The direction a pawn faces while using the object, relative to the object.
+    pub facing: f32,
+    reserved_by: Option<Entity>,
+}
+
+/// A usage slot of an object held by a task. The slot is released when the task is removed.
+#[derive(Debug, Clone, Copy, Component, Serialize, Deserialize)]
+#[component(immutable)]
+pub struct Reservation {
+    object: Entity,
+    slot: usize,
+}
+
+#[derive(SystemParam)]
+pub struct ReservationQuery<'w, 's> {
+    object_q: Query<'w, 's, (&'static mut UsageSlots, &'static GlobalTransform)>,
+}
+
+impl UsageSlots {
+    pub fn new(slots: impl IntoIterator<Item = UsageSlot>) -> Self {
+        UsageSlots {
+            slots: slots.into_iter().collect(),
+        }
+    }
+
+    /// An object used by one pawn at a time, standing at the given offset.
+    pub fn single(offset: Vec2, facing: f32) -> Self {
+        UsageSlots::new([UsageSlot::new(offset, facing)])
+    }
+
+    pub fn slots(&self) -> &[UsageSlot] {
+        &self.slots
+    }
+
+    /// Marks the slot of an existing reservation as held by a task, such as when loading a save.
+    pub(crate) fn restore(&mut self, reservation: &Reservation, task: Entity) {
+        if let Some(slot) = self.slots.get_mut(reservation.slot) {
+            slot.reserved_by = Some(task);
+        }
+    }
+}
+
+impl UsageSlot {
+    pub fn new(offset: Vec2, facing: f32) -> Self {
+        UsageSlot {
+            offset,
+            facing,
+            reserved_by: None,
+        }
+    }
+
+    pub fn reserved_by(&self) -> Option<Entity> {
+        self.reserved_by
+    }
+}
+
+impl Reservation {
+    pub fn object(&self) -> Entity {
+        self.object
+    }
+
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+}
+
+impl MapEntities for Reservation {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.object = entity_mapper.get_mapped(self.object);
+    }
+}
+
+impl ReservationQuery<'_, '_> {
+    /// Returns whether an object has a free slot.
+    pub fn available(&self, object: Entity) -> bool {
+        self.object_q
+            .get(object)
+            .is_ok_and(|(slots, _)| slots.slots.iter().any(|slot| slot.reserved_by.is_none()))
+    }
+
+    /// Reserves a free slot of an object for a task. The returned component should be inserted on
+    /// the task so that the slot is released when the task is removed.
+    pub fn reserve(&mut self, object: Entity, task: Entity) -> Option<Reservation> {
+        let (mut slots, _) = self.object_q.get_mut(object).ok()?;
+        let slot = slots
+            .slots
+            .iter()
+            .position(|slot| slot.reserved_by.is_none())?;
+        slots.slots[slot].reserved_by = Some(task);
+        Some(Reservation { object, slot })
+    }
+
+    /// Returns the world-space position and facing angle of a reserved slot.
+    pub fn interaction_point(&self, reservation: &Reservation) -> Option<(Vec2, f32)> {
+        let (slots, transform) = self.object_q.get(reservation.object).ok()?;
+        let slot = slots.slots.get(reservation.slot)?;
+        Some((
+            transform.transform_point(slot.offset.extend(0.)).xy(),
+            transform.rotation().to_euler(EulerRot::ZYX).0 + slot.facing,
+        ))
+    }
+
+    pub fn release(&mut self, reservation: &Reservation, task: Entity) {
+        let Ok((mut slots, _)) = self.object_q.get_mut(reservation.object) else {
+            return;
+        };
+        if let Some(slot) = slots
+            .slots
+            .get_mut(reservation.slot)
+            .filter(|slot| slot.reserved_by == Some(task))
+        {
+            slot.reserved_by = None;
+        }
+    }
+}
+
+/// Reserves a slot of an object, then spawns a task for the actor to move to its interaction point.
+/// The task is built from the steps of the path and the interaction point.
+pub fn path_to_object<B: Bundle>(
+    commands: &mut Commands,
+    reservation_q: &mut ReservationQuery,
+    path_q: &PathQuery,
+    actor: Entity,
+    object: Entity,
+    task: impl FnOnce(VecDeque<Vec2>, Vec2) -> B,
+) -> Option<Entity> {
+    let id = commands.spawn_empty().id();
+    let path = reservation_q.reserve(object, id).and_then(|reservation| {
+        let path = reservation_q
+            .interaction_point(&reservation)
+            .and_then(|(point, _)| {
+                let steps = path_q.steps(actor, point);
+                if steps.is_none() {
+                    trace::path_not_found(commands, actor, point);
+                }
+                Some((steps?, point))
+            });
+        if path.is_none() {
+            reservation_q.release(&reservation, id);
+        }
+        Some((path?, reservation))
+    });
+
+    match path {
+        Some(((steps, point), reservation)) => {
+            commands
+                .entity(id)
+                .insert((task(steps, point), reservation));
+            Some(id)
+        }
+        None => {
+            commands.entity(id).despawn();
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pawn::ai::{self, Actor, Task};
+
+    fn reserve(
+        In((object, task)): In<(Entity, Entity)>,
+        mut commands: Commands,
+        mut reservation_q: ReservationQuery,
+    ) -> bool {
+        let reservation = reservation_q.reserve(object, task);
+        if let Some(reservation) = reservation {
+            commands.entity(task).insert(reservation);
+        }
+        reservation.is_some()
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut world = World::new();
+        world.add_observer(ai::task_removed);
+
+        let object = world
+            .spawn((
+                UsageSlots::single(Vec2::X, 0.),
+                GlobalTransform::from_xyz(1., 2., 0.),
+            ))
+            .id();
+        let [first, second] = [(); 2].map(|()| {
+            let actor = world.spawn(Actor::default()).id();
+            world.spawn(Task::new(actor)).id()
+        });
+
+        assert!(
+            world
+                .run_system_cached_with(reserve, (object, first))
+                .unwrap()
+        );
+        assert!(
+            !world
+                .run_system_cached_with(reserve, (object, second))
+                .unwrap()
+        );
+
+        // Removing the first task frees the slot for the second.
+        world.despawn(first);
+        assert!(
+            world
+                .run_system_cached_with(reserve, (object, second))
+                .unwrap()
+        );
+        assert_eq!(
+            world.get::<UsageSlots>(object).unwrap().slots()[0].reserved_by(),
+            Some(second)
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{
+    ecs::entity::{EntityHashSet, MapEntities},
+    prelude::*,
+};
+use pb_util::rng::LocalRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    item::{HeldBy, Inventory, Item},
+    map::room::{Designation, DesignationQuery, RoomContents},
+    pawn::{
+        ai::{
+            Actor, Task,
+            escape::Escaping,
+            interaction::{self, InteractionFinished, InteractionKind},
+            path::{MovementQuery, PathQuery},
+            perception::Perception,
+        },
+        role::{Guard, Prisoner},
+    },
+    security::SecurityEvent,
+};
+
+/// The chance that an idle guard starts a search each time searches are assigned.
+const SEARCH_CHANCE: f64 = 0.05;
+/// The time a guard spends searching a room once it arrives.
+const ROOM_SEARCH_DURATION: Duration = Duration::from_secs(10);
+
+/// A task to walk to a room and search the floor for contraband.
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
+pub struct RoomSearchTask {
+    room: Entity,
+    steps: VecDeque<Vec2>,
+    elapsed: Duration,
+}
+
+impl RoomSearchTask {
+    pub fn room(&self) -> Entity {
+        self.room
+    }
+}
+
+impl MapEntities for RoomSearchTask {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.room = entity_mapper.get_mapped(self.room);
+    }
+}
+
+/// Has some idle guards search an idle prisoner they can see, or otherwise a random cell.
+pub fn assign(
+    mut commands: Commands,
+    guard_q: Query<(Entity, &Actor, &Perception), With<Guard>>,
+    prisoner_q: Query<&Actor, (With<Prisoner>, Without<Escaping>)>,
+    designation_q: DesignationQuery,
+    path_q: PathQuery,
+    mut rng: LocalRng,
+) {
+    let mut searched = EntityHashSet::default();
+    for (guard, actor, perception) in &guard_q {
+        if actor.task().is_some() || !rng.random_bool(SEARCH_CHANCE) {
+            continue;
+        }
+
+        let prisoner = perception.visible().find(|&pawn| {
+            !searched.contains(&pawn)
+                && prisoner_q
+                    .get(pawn)
+                    .is_ok_and(|actor| actor.task().is_none())
+        });
+        if let Some(prisoner) = prisoner {
+            info!("guard {guard} is searching {prisoner}");
+            interaction::start(&mut commands, InteractionKind::Search, guard, prisoner);
+            searched.insert(prisoner);
+            continue;
+        }
+
+        let Some(room) = path_q
+            .map(guard)
+            .and_then(|map| designation_q.random_room(map, Designation::Cell, &mut rng))
+        else {
+            continue;
+        };
+        let Some(steps) = designation_q
+            .point_in(room, &mut rng)
+            .and_then(|target| path_q.steps(guard, target))
+        else {
+            continue;
+        };
+
+        info!("guard {guard} is searching room {room}");
+        commands.spawn((
+            Task::new(guard),
+            RoomSearchTask {
+                room,
+                steps,
+                elapsed: Duration::ZERO,
+            },
+        ));
+    }
+}
+
+/// Moves guards to the rooms they are searching, and confiscates any contraband lying there once
+/// they have finished.
+pub fn update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut task_q: Query<(Entity, &Task, &mut RoomSearchTask)>,
+    room_q: Query<&RoomContents>,
+    item_q: Query<(Entity, &Item), Without<HeldBy>>,
+    mut movement_q: MovementQuery,
+    mut security_e: EventWriter<SecurityEvent>,
+) -> Result {
+    for (id, task, mut search) in &mut task_q {
+        let guard = task.actor();
+        if !search.steps.is_empty() {
+            movement_q.steer(guard, &mut search.steps)?;
+            continue;
+        }
+
+        movement_q.act(guard, 0., 0., 0.)?;
+        search.elapsed += time.delta();
+        if search.elapsed < ROOM_SEARCH_DURATION {
+            continue;
+        }
+
+        if let Ok(contents) = room_q.get(search.room) {
+            for (item_id, item) in item_q.iter_many(contents.iter()) {
+                confiscate(&mut commands, &mut security_e, guard, item_id, item, None);
+            }
+        }
+        commands.entity(id).despawn();
+    }
+
+    Ok(())
+}
+
+/// Confiscates any contraband carried by pawns once a guard has finished searching them.
+pub fn finished(
+    mut commands: Commands,
+    mut finished_e: EventReader<InteractionFinished>,
+    inventory_q: Query<&Inventory>,
+    item_q: Query<(Entity, &Item)>,
+    mut security_e: EventWriter<SecurityEvent>,
+) {
+    for event in finished_e.read() {
+        if event.kind != InteractionKind::Search {
+            continue;
+        }
+        let Ok(inventory) = inventory_q.get(event.target) else {
+            continue;
+        };
+
+        for (item_id, item) in item_q.iter_many(inventory.iter()) {
+            confiscate(
+                &mut commands,
+                &mut security_e,
+                event.initiator,
+                item_id,
+                item,
+                Some(event.target),
+            );
+        }
+    }
+}
+
+fn confiscate(
+    commands: &mut Commands,
+    security_e: &mut EventWriter<SecurityEvent>,
+    guard: Entity,
+    id: Entity,
+    item: &Item,
+    owner: Option<Entity>,
+) {
+    if !item.contraband {
+        return;
+    }
+
+    info!("guard {guard} confiscated {:?} {id}", item.kind);
+    commands.entity(id).despawn();
+    security_e.write(SecurityEvent::ContrabandFound {
+        guard,
+        kind: item.kind,
+        owner,
+    });
+}
@@ -0,0 +1,218 @@
+use std::{collections::VecDeque, fmt, time::Duration};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::pawn::ai::{
+    Task, TaskFailed, TaskFailure,
+    interaction::{InteractionKind, InteractionTask},
+    path::{PathTarget, PathTask},
+};
+
+/// An opt-in record of the most recent decisions made for a pawn, for debugging its behaviour.
+///
+/// Only pawns with this component are traced, and old entries are discarded once it is full.
+#[derive(Debug, Clone, Component, TypePath, Serialize, Deserialize)]
+pub struct BehaviourTrace {
+    capacity: usize,
+    entries: VecDeque<TraceEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// The simulation time at which the entry was recorded.
+    pub time: Duration,
+    pub event: TraceEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceEvent {
+    TaskStarted { task: Entity, kind: TaskKind },
+    TaskEnded { task: Entity },
+    TaskFailed { task: Entity, reason: TaskFailure },
+    PathFound { target: Vec2, steps: usize },
+    PathNotFound { target: Vec2 },
+    Stuck { repaths: u32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Path {
+        target: Vec2,
+        steps: Option<usize>,
+    },
+    Interaction {
+        kind: InteractionKind,
+        partner: Entity,
+    },
+    Other,
+}
+
+impl BehaviourTrace {
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    pub fn new(capacity: usize) -> Self {
+        BehaviourTrace {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &TraceEntry> {
+        self.entries.iter()
+    }
+
+    pub fn push(&mut self, time: Duration, event: TraceEvent) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(TraceEntry { time, event });
+    }
+}
+
+impl Default for BehaviourTrace {
+    fn default() -> Self {
+        BehaviourTrace::new(BehaviourTrace::DEFAULT_CAPACITY)
+    }
+}
+
+/// Records that no path to the target could be found for an entity, if it is being traced. Paths
+/// which are found are recorded when their task is added.
+pub fn path_not_found(commands: &mut Commands, entity: Entity, target: Vec2) {
+    commands.queue(move |world: &mut World| {
+        let time = world.resource::<Time<Fixed>>().elapsed();
+        if let Some(mut trace) = world.get_mut::<BehaviourTrace>(entity) {
+            trace.push(time, TraceEvent::PathNotFound { target });
+        }
+    });
+}
+
+/// Records an event for an entity, if it is being traced.
+pub fn record(
+    trace_q: &mut Query<&mut BehaviourTrace>,
+    time: Duration,
+    entity: Entity,
+    event: TraceEvent,
+) {
+    if let Ok(mut trace) = trace_q.get_mut(entity) {
+        trace.push(time, event);
+    }
+}
+
+pub fn task_added(
+    trigger: Trigger<OnInsert, Task>,
+    task_q: Query<(
+        &Task,
+        Option<&PathTarget>,
+        Option<&PathTask>,
+        Option<&InteractionTask>,
+    )>,
+    mut trace_q: Query<&mut BehaviourTrace>,
+    time: Res<Time<Fixed>>,
+) -> Result {
+    let (task, target, path, interaction) = task_q.get(trigger.target())?;
+    if !trace_q.contains(task.actor()) {
+        return Ok(());
+    }
+
+    if let Some((target, steps)) = target.zip(path.and_then(PathTask::steps)) {
+        record(
+            &mut trace_q,
+            time.elapsed(),
+            task.actor(),
+            TraceEvent::PathFound {
+                target: target.0,
+                steps: steps.len(),
+            },
+        );
+    }
+
+    let kind = match (target, interaction) {
+        (Some(target), _) => TaskKind::Path {
+            target: target.0,
+            steps: path.and_then(PathTask::steps).map(VecDeque::len),
+        },
+        (None, Some(interaction)) => TaskKind::Interaction {
+            kind: interaction.kind(),
+            partner: interaction.partner(),
+        },
+        (None, None) => TaskKind::Other,
+    };
+    record(
+        &mut trace_q,
+        time.elapsed(),
+        task.actor(),
+        TraceEvent::TaskStarted {
+            task: trigger.target(),
+            kind,
+        },
+    );
+    Ok(())
+}
+
+pub fn task_removed(
+    trigger: Trigger<OnReplace, Task>,
+    task_q: Query<&Task>,
+    mut trace_q: Query<&mut BehaviourTrace>,
+    time: Res<Time<Fixed>>,
+) -> Result {
+    let task = task_q.get(trigger.target())?;
+    record(
+        &mut trace_q,
+        time.elapsed(),
+        task.actor(),
+        TraceEvent::TaskEnded {
+            task: trigger.target(),
+        },
+    );
+    Ok(())
+}
+
+pub fn task_failed(
+    mut failed_e: EventReader<TaskFailed>,
+    mut trace_q: Query<&mut BehaviourTrace>,
+    time: Res<Time<Fixed>>,
+) {
+    for event in failed_e.read() {
+        record(
+            &mut trace_q,
+            time.elapsed(),
+            event.actor,
+            TraceEvent::TaskFailed {
+                task: event.task,
+                reason: event.reason,
+            },
+        );
+    }
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>8.2}s ", self.time.as_secs_f32())?;
+        match &self.event {
+            TraceEvent::TaskStarted { task, kind } => match kind {
+                TaskKind::Path {
+                    target,
+                    steps: Some(steps),
+                } => write!(f, "started {task}: move to {target} ({steps} steps)"),
+                TaskKind::Path {
+                    target,
+                    steps: None,
+                } => write!(f, "started {task}: move to {target}"),
+                TaskKind::Interaction { kind, partner } => {
+                    write!(f, "started {task}: {kind:?} with {partner}")
+                }
+                TaskKind::Other => write!(f, "started {task}"),
+            },
+            TraceEvent::TaskEnded { task } => write!(f, "ended {task}"),
+            TraceEvent::TaskFailed { task, reason } => write!(f, "failed {task}: {reason:?}"),
+            TraceEvent::PathFound { target, steps } => {
+                write!(f, "found path to {target} ({steps} steps)")
+            }
+            TraceEvent::PathNotFound { target } => write!(f, "no path to {target}"),
+            TraceEvent::Stuck { repaths } => write!(f, "stuck (re-path {repaths})"),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The injuries a pawn has suffered, from 0 (healthy) to 1 (critical).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Health {
+    injury: f32,
+}
+
+impl Health {
+    /// The injury above which a pawn seeks treatment.
+    pub const TREATMENT_THRESHOLD: f32 = 0.25;
+    /// The injury above which a pawn can no longer move.
+    pub const INCAPACITATED_THRESHOLD: f32 = 0.8;
+    /// The fraction of its speed an injured pawn loses just before it is incapacitated.
+    const MAX_SLOWDOWN: f32 = 0.6;
+
+    pub fn injury(&self) -> f32 {
+        self.injury
+    }
+
+    pub fn injure(&mut self, amount: f32) {
+        self.injury = (self.injury + amount).clamp(0., 1.);
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        self.injure(-amount);
+    }
+
+    pub fn is_injured(&self) -> bool {
+        self.injury > 0.
+    }
+
+    pub fn needs_treatment(&self) -> bool {
+        self.injury >= Self::TREATMENT_THRESHOLD
+    }
+
+    pub fn is_incapacitated(&self) -> bool {
+        self.injury >= Self::INCAPACITATED_THRESHOLD
+    }
+
+    /// The fraction of the normal limits a pawn can move at.
+    pub fn speed_factor(&self) -> f32 {
+        if self.is_incapacitated() {
+            0.
+        } else {
+            1. - Self::MAX_SLOWDOWN * self.injury / Self::INCAPACITATED_THRESHOLD
+        }
+    }
+}
@@ -1,21 +1,30 @@
 pub mod ai;
+pub mod health;
+pub mod mood;
+pub mod role;
 
 use std::f32::consts::{PI, TAU};
 
-use ai::Actor;
+use ai::{Actor, perception::Perception};
 use approx::relative_ne;
 use avian2d::prelude::*;
 use bevy::prelude::*;
+use health::Health;
+use mood::Mood;
 use pb_util::math::to_finite_f32_lossy;
 use serde::{Deserialize, Serialize};
 
-use crate::layer::Layer;
+use crate::{determinism::Deterministic, layer::Layer, pawn::role::Role};
 
 #[derive(Debug, Default, Copy, Clone, Component, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
 #[require(
     Name::new("Pawn"),
     Actor,
+    Perception,
+    Mood,
+    Health,
+    MovementStats,
     RigidBody::Dynamic,
     Collider::circle(Pawn::RADIUS),
     CollisionLayers::new(Layer::Pawn, LayerMask::ALL),
@@ -30,6 +39,16 @@ pub struct Pawn {
     pub torque: f32,
 }
 
+/// The limits of a pawn's movement.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct MovementStats {
+    pub max_acceleration: f32,
+    pub max_velocity: f32,
+    pub max_torque: f32,
+    pub max_angular_velocity: f32,
+}
+
 #[derive(Default, Clone, Bundle)]
 pub struct PawnBundle {
     pawn: Pawn,
@@ -41,11 +60,6 @@ pub struct PawnBundle {
 impl Pawn {
     pub const RADIUS: f32 = 0.16;
     pub const AREA: f32 = Self::RADIUS * Self::RADIUS * PI;
-    pub const MAX_ACCELERATION: f32 = 0.68;
-    pub const MAX_VELOCITY: f32 = 1.5;
-    pub const REVERSE_VELOCITY: f32 = 0.7;
-    pub const MAX_TORQUE: f32 = TAU;
-    pub const MAX_ANGULAR_VELOCITY: f32 = PI;
     pub const VISION_RADIUS: f32 = 4.;
 
     pub fn update_movement(&mut self, angle: f32, accel: f32, torque: f32) {
@@ -55,6 +69,42 @@ impl Pawn {
     }
 }
 
+impl MovementStats {
+    pub const DEFAULT: Self = MovementStats {
+        max_acceleration: 0.68,
+        max_velocity: 1.5,
+        max_torque: TAU,
+        max_angular_velocity: PI,
+    };
+    pub const STAFF: Self = MovementStats {
+        max_acceleration: 0.8,
+        max_velocity: 1.8,
+        ..Self::DEFAULT
+    };
+
+    pub fn for_role(role: Option<Role>) -> Self {
+        match role {
+            Some(Role::Guard | Role::Worker) => Self::STAFF,
+            Some(Role::Prisoner) | None => Self::DEFAULT,
+        }
+    }
+
+    /// Returns the limits of a pawn with the given health.
+    pub fn limited(&self, health: &Health) -> Self {
+        MovementStats {
+            max_velocity: self.max_velocity * health.speed_factor(),
+            max_angular_velocity: self.max_angular_velocity * health.speed_factor(),
+            ..*self
+        }
+    }
+}
+
+impl Default for MovementStats {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 impl PawnBundle {
     pub fn new(position: Vec2, rotation: f32) -> Self {
         Self {
@@ -70,59 +120,112 @@ impl PawnBundle {
 pub fn movement(
     mut pawn_q: Query<(
         &Pawn,
+        &MovementStats,
+        &Health,
         &Rotation,
         &LinearVelocity,
         &AngularVelocity,
         &mut ExternalForce,
         &mut ExternalTorque,
     )>,
+    deterministic: Option<Res<Deterministic>>,
 ) {
-    pawn_q.par_iter_mut().for_each(
-        |(pawn, rotation, linear_velocity, angular_velocity, mut force, mut torque)| {
-            force.persistent = false;
-            torque.persistent = false;
-
-            if relative_ne!(pawn.dir, Vec2::ZERO) {
-                let movement_dir = rotation * pawn.dir;
-                force.set_force(movement_dir.normalize() * pawn.accel * Pawn::MAX_ACCELERATION);
-            } else if relative_ne!(linear_velocity.0, Vec2::ZERO) {
-                force.set_force((-linear_velocity.0).normalize() * Pawn::MAX_ACCELERATION);
-            }
-            if relative_ne!(pawn.torque, 0.) {
-                torque.apply_torque(pawn.torque * Pawn::MAX_TORQUE);
-            } else if relative_ne!(angular_velocity.0, 0.) {
-                torque.apply_torque((-angular_velocity.0).signum() * Pawn::MAX_TORQUE);
-            }
-        },
-    );
+    let update = |(
+        pawn,
+        stats,
+        health,
+        rotation,
+        linear_velocity,
+        angular_velocity,
+        mut force,
+        mut torque,
+    ): (
+        &Pawn,
+        &MovementStats,
+        &Health,
+        &Rotation,
+        &LinearVelocity,
+        &AngularVelocity,
+        Mut<ExternalForce>,
+        Mut<ExternalTorque>,
+    )| {
+        force.persistent = false;
+        torque.persistent = false;
+
+        let pawn = if health.is_incapacitated() {
+            &Pawn::default()
+        } else {
+            pawn
+        };
+
+        if relative_ne!(pawn.dir, Vec2::ZERO) {
+            let movement_dir = rotation * pawn.dir;
+            force.set_force(movement_dir.normalize() * pawn.accel * stats.max_acceleration);
+        } else if relative_ne!(linear_velocity.0, Vec2::ZERO) {
+            force.set_force((-linear_velocity.0).normalize() * stats.max_acceleration);
+        }
+        if relative_ne!(pawn.torque, 0.) {
+            torque.apply_torque(pawn.torque * stats.max_torque);
+        } else if relative_ne!(angular_velocity.0, 0.) {
+            torque.apply_torque((-angular_velocity.0).signum() * stats.max_torque);
+        }
+    };
+
+    if deterministic.is_some() {
+        pawn_q.iter_mut().for_each(update);
+    } else {
+        pawn_q.par_iter_mut().for_each(update);
+    }
 }
 
 pub fn clamp_velocity(
-    mut pawn_q: Query<(&Rotation, &mut LinearVelocity, &mut AngularVelocity), With<Pawn>>,
+    mut pawn_q: Query<
+        (
+            &MovementStats,
+            &Health,
+            &Rotation,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+        ),
+        With<Pawn>,
+    >,
+    deterministic: Option<Res<Deterministic>>,
 ) {
-    pawn_q
-        .par_iter_mut()
-        .for_each(|(rotation, mut linear_velocity, mut angular_velocity)| {
-            let mut velocity = linear_velocity.length();
-
-            if relative_ne!(velocity, 0.0) {
-                let forward_velocity = rotation.inverse() * linear_velocity.0;
-                let angle_t = forward_velocity.to_angle().abs() / PI;
-                let max_velocity = Pawn::MAX_VELOCITY.lerp(Pawn::MAX_VELOCITY / 2., angle_t);
-
-                if velocity > max_velocity {
-                    linear_velocity.0 *= max_velocity / velocity;
-                    velocity = max_velocity;
-                }
-            }
+    let update = |(stats, health, rotation, mut linear_velocity, mut angular_velocity): (
+        &MovementStats,
+        &Health,
+        &Rotation,
+        Mut<LinearVelocity>,
+        Mut<AngularVelocity>,
+    )| {
+        let stats = stats.limited(health);
+        let mut velocity = linear_velocity.length();
+        let limit = stats.max_velocity;
 
-            if relative_ne!(angular_velocity.0, 0.0) {
-                let max_angular_velocity = Pawn::MAX_ANGULAR_VELOCITY.lerp(
-                    Pawn::MAX_ANGULAR_VELOCITY / 2.,
-                    velocity / Pawn::MAX_VELOCITY,
-                );
-                angular_velocity.0 =
-                    angular_velocity.clamp(-max_angular_velocity, max_angular_velocity);
+        if relative_ne!(velocity, 0.0) {
+            let forward_velocity = rotation.inverse() * linear_velocity.0;
+            let angle_t = forward_velocity.to_angle().abs() / PI;
+            let max_velocity = limit.lerp(limit / 2., angle_t);
+
+            if velocity > max_velocity {
+                linear_velocity.0 *= max_velocity / velocity;
+                velocity = max_velocity;
             }
-        });
+        }
+
+        if relative_ne!(angular_velocity.0, 0.0) {
+            let limit_t = if limit > 0. { velocity / limit } else { 1. };
+            let max_angular_velocity = stats
+                .max_angular_velocity
+                .lerp(stats.max_angular_velocity / 2., limit_t);
+            angular_velocity.0 =
+                angular_velocity.clamp(-max_angular_velocity, max_angular_velocity);
+        }
+    };
+
+    if deterministic.is_some() {
+        pawn_q.iter_mut().for_each(update);
+    } else {
+        pawn_q.par_iter_mut().for_each(update);
+    }
 }
@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How content a pawn is, from -1 (miserable) to 1 (happy).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Mood(f32);
+
+impl Mood {
+    pub fn get(&self) -> f32 {
+        self.0
+    }
+
+    pub fn adjust(&mut self, delta: f32) {
+        self.0 = (self.0 + delta).clamp(-1., 1.);
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::{ecs::query::QueryData, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Prisoner,
+    Guard,
+    Worker,
+}
+
+#[derive(Debug, Copy, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Prisoner {
+    /// The prisoner's identification number, or zero to assign the next free number when added.
+    pub number: u32,
+    pub sentence_days: u32,
+}
+
+#[derive(Debug, Default, Copy, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Guard {
+    pub shift: Shift,
+}
+
+#[derive(Debug, Default, Copy, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Worker {
+    pub job: Job,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Shift {
+    #[default]
+    Day,
+    Night,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Job {
+    #[default]
+    Cook,
+    Cleaner,
+    Gardener,
+    Medic,
+}
+
+#[derive(QueryData)]
+pub struct RoleQuery {
+    pub prisoner: Option<&'static Prisoner>,
+    pub guard: Option<&'static Guard>,
+    pub worker: Option<&'static Worker>,
+}
+
+pub fn prisoner_added(
+    trigger: Trigger<OnAdd, Prisoner>,
+    mut prisoner_q: Query<&mut Prisoner>,
+) -> Result {
+    let next_number = prisoner_q
+        .iter()
+        .map(|prisoner| prisoner.number)
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let mut prisoner = prisoner_q.get_mut(trigger.target())?;
+    if prisoner.number == 0 {
+        prisoner.number = next_number;
+    }
+    Ok(())
+}
+
+impl Prisoner {
+    pub const DEFAULT_SENTENCE_DAYS: u32 = 365;
+}
+
+impl Default for Prisoner {
+    fn default() -> Self {
+        Prisoner {
+            number: 0,
+            sentence_days: Prisoner::DEFAULT_SENTENCE_DAYS,
+        }
+    }
+}
+
+impl RoleQueryItem<'_> {
+    pub fn role(&self) -> Option<Role> {
+        if self.prisoner.is_some() {
+            Some(Role::Prisoner)
+        } else if self.guard.is_some() {
+            Some(Role::Guard)
+        } else if self.worker.is_some() {
+            Some(Role::Worker)
+        } else {
+            None
+        }
+    }
+}
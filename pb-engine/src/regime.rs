@@ -0,0 +1,100 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{clock::Clock, map::room::Designation, root::Root};
+
+/// The daily timetable followed by prisoners, with one activity for each hour of the day.
+#[derive(Debug, Clone, PartialEq, Eq, Resource, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Regime {
+    hours: [Activity; Regime::HOURS],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activity {
+    Sleep,
+    Eat,
+    Work,
+    Yard,
+    Lockdown,
+}
+
+impl Regime {
+    pub const HOURS: usize = 24;
+
+    pub fn activity(&self, hour: u32) -> Activity {
+        self.hours[hour as usize % Regime::HOURS]
+    }
+
+    pub fn current(&self, clock: &Clock) -> Activity {
+        self.activity(clock.hour())
+    }
+
+    pub fn set(&mut self, hour: u32, activity: Activity) {
+        self.hours[hour as usize % Regime::HOURS] = activity;
+    }
+
+    pub fn hours(&self) -> impl Iterator<Item = (u32, Activity)> + '_ {
+        self.hours
+            .iter()
+            .enumerate()
+            .map(|(hour, &activity)| (hour as u32, activity))
+    }
+}
+
+impl Default for Regime {
+    fn default() -> Self {
+        use Activity::*;
+
+        Regime {
+            hours: [
+                Sleep, Sleep, Sleep, Sleep, Sleep, Sleep, Sleep, Eat, Work, Work, Work, Work, Eat,
+                Yard, Yard, Work, Work, Eat, Yard, Yard, Yard, Lockdown, Sleep, Sleep,
+            ],
+        }
+    }
+}
+
+impl Activity {
+    pub const ALL: [Activity; 5] = [
+        Activity::Sleep,
+        Activity::Eat,
+        Activity::Work,
+        Activity::Yard,
+        Activity::Lockdown,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Activity::ALL.iter().position(|&a| a == self).unwrap();
+        Activity::ALL[(index + 1) % Activity::ALL.len()]
+    }
+
+    /// The kind of room prisoners should be in during this activity.
+    pub fn designation(self) -> Designation {
+        match self {
+            Activity::Sleep | Activity::Lockdown => Designation::Cell,
+            Activity::Eat => Designation::Canteen,
+            Activity::Work => Designation::Workshop,
+            Activity::Yard => Designation::Yard,
+        }
+    }
+}
+
+impl fmt::Display for Activity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Activity::Sleep => write!(f, "Sleep"),
+            Activity::Eat => write!(f, "Eat"),
+            Activity::Work => write!(f, "Work"),
+            Activity::Yard => write!(f, "Yard"),
+            Activity::Lockdown => write!(f, "Lockdown"),
+        }
+    }
+}
+
+pub fn root_added(_: Trigger<OnAdd, Root>, mut commands: Commands) {
+    commands.insert_resource(Regime::default());
+}
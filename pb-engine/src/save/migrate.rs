@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use serde_json::{Map, Value, json};
+
+use crate::save::SaveModel;
+
+/// The version of the save format written by this build.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Upgrades a save payload from each version of the format to the next.
+///
+/// The migration at index `n` takes a payload written by version `n` to version `n + 1`. Version 0
+/// covers documents written before saves were versioned.
+const MIGRATIONS: [fn(&mut Value) -> Result; CURRENT_VERSION as usize] = [v0_to_v1, v1_to_v2];
+
+/// Reads a save document of any supported version, upgrading it to the current format.
+pub fn load(document: Value) -> Result<SaveModel> {
+    let (version, mut save) = split(document)?;
+    upgrade(version, &mut save)?;
+    serde_json::from_value(save)
+        .map_err(|error| format!("invalid save (format version {version}): {error}").into())
+}
+
+/// Separates the version from the payload of a save document.
+fn split(document: Value) -> Result<(u32, Value)> {
+    let Value::Object(mut object) = document else {
+        return Err("invalid save: expected an object".into());
+    };
+
+    let Some(version) = object.remove("version") else {
+        return Ok((0, Value::Object(object)));
+    };
+    let version = version
+        .as_u64()
+        .and_then(|version| u32::try_from(version).ok())
+        .ok_or_else(|| format!("invalid save: unrecognized version '{version}'"))?;
+    let save = object
+        .remove("save")
+        .ok_or("invalid save: missing 'save' field")?;
+    Ok((version, save))
+}
+
+fn upgrade(version: u32, save: &mut Value) -> Result {
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "save was created by a newer version of the game (format version {version}, \
+            this version supports up to {CURRENT_VERSION})"
+        )
+        .into());
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(save).map_err(|error| {
+            format!(
+                "failed to upgrade save from format version {from} to {}: {error}",
+                from + 1
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Version 1 added the version envelope without changing the payload.
+fn v0_to_v1(save: &mut Value) -> Result {
+    object(save)?;
+    Ok(())
+}
+
+/// Version 2 added ids to patrol routes, so that the patrols of guards can refer to them.
+fn v1_to_v2(save: &mut Value) -> Result {
+    let mut next_id = max_id(save).map_or(Entity::from_raw(0).to_bits(), |id| id + 1);
+
+    let Some(maps) = object(save)?.get_mut("maps") else {
+        return Ok(());
+    };
+    for map in array(maps)? {
+        let Some(routes) = object(map)?.get_mut("patrol_routes") else {
+            continue;
+        };
+        for route in array(routes)? {
+            *route = json!({ "id": next_id, "route": route.take() });
+            next_id += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the largest entity id in the payload, for migrations which need to add entities.
+fn max_id(value: &Value) -> Option<u64> {
+    match value {
+        Value::Object(object) => object
+            .iter()
+            .filter_map(|(key, value)| match key.as_str() {
+                "id" => value.as_u64(),
+                _ => max_id(value),
+            })
+            .max(),
+        Value::Array(array) => array.iter().filter_map(max_id).max(),
+        _ => None,
+    }
+}
+
+/// Returns the fields of an object in the payload, for migrations which need to edit them.
+fn object(value: &mut Value) -> Result<&mut Map<String, Value>> {
+    value
+        .as_object_mut()
+        .ok_or_else(|| "expected an object".into())
+}
+
+fn array(value: &mut Value) -> Result<&mut Vec<Value>> {
+    value
+        .as_array_mut()
+        .ok_or_else(|| "expected an array".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_newer_version() {
+        let error = load(json!({
+            "version": CURRENT_VERSION + 1,
+            "save": { "pawns": [], "maps": [] },
+        }))
+        .unwrap_err();
+        assert!(error.to_string().contains("newer version"));
+    }
+
+    #[test]
+    fn test_patrol_route_ids() {
+        let save = load(json!({
+            "version": 1,
+            "save": {
+                "pawns": [{
+                    "id": 4294967297u64,
+                    "position": [0.0, 0.0],
+                    "rotation": 0.0,
+                    "linear_velocity": [0.0, 0.0],
+                    "angular_velocity": 0.0,
+                }],
+                "maps": [{
+                    "id": 4294967298u64,
+                    "corners": [],
+                    "walls": [],
+                    "rooms": [],
+                    "patrol_routes": [{ "waypoints": [] }, { "waypoints": [] }],
+                }],
+            },
+        }))
+        .unwrap();
+
+        let routes = &save.maps[0].patrol_routes;
+        assert_eq!(routes[0].id.to_bits(), 4294967299);
+        assert_eq!(routes[1].id.to_bits(), 4294967300);
+    }
+
+    #[test]
+    fn test_unversioned() {
+        let save = load(json!({ "pawns": [], "maps": [] })).unwrap();
+        assert!(save.pawns.is_empty());
+    }
+}
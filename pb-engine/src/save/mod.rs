@@ -1,44 +1,162 @@
+pub mod migrate;
+
+use std::{collections::VecDeque, iter};
+
 use avian2d::prelude::*;
 use bevy::{
-    ecs::{entity::EntityHashMap, system::SystemParam},
+    ecs::{
+        entity::{EntityHashMap, MapEntities},
+        query::QueryData,
+        system::SystemParam,
+    },
     prelude::*,
 };
 use glam::Vec2;
-use serde::{Deserialize, Serialize};
+use pb_util::rng::RngSeed;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
 
 use crate::{
     EngineState,
-    map::{Map, corner::Corner, door::Door, room::Room, wall::Wall},
-    pawn::{Pawn, PawnBundle},
+    clock::Clock,
+    determinism::Deterministic,
+    item::{HeldBy, Item},
+    map::{
+        Map,
+        corner::Corner,
+        door::Door,
+        patrol::PatrolRoute,
+        room::{Designation, Room},
+        wall::Wall,
+    },
+    pawn::{
+        MovementStats, Pawn, PawnBundle,
+        ai::{
+            Task,
+            escape::Escaping,
+            group::{GroupTask, MoveGroup},
+            interaction::InteractionTask,
+            item::{DropTask, PickUpTask},
+            path::{PathProgress, PathTarget, PathTask},
+            patrol::Patrolling,
+            reservation::{Reservation, UsageSlots},
+            search::RoomSearchTask,
+        },
+        health::Health,
+        mood::Mood,
+        role::{Guard, Prisoner, RoleQuery, RoleQueryItem, Worker},
+    },
+    regime::Regime,
     root::Root,
+    statistics::Statistics,
 };
 
 #[derive(SystemParam)]
 pub struct SaveParam<'w, 's> {
     state: Res<'w, State<EngineState>>,
-    pawn_q: Query<
+    pawn_q: Query<'w, 's, PawnQuery>,
+    task_q: Query<'w, 's, TaskQuery>,
+    group_q: Query<'w, 's, &'static MoveGroup>,
+    item_q: Query<
         'w,
         's,
         (
             Entity,
-            &'static Pawn,
+            &'static Item,
             &'static ChildOf,
-            &'static Position,
-            &'static Rotation,
-            &'static LinearVelocity,
-            &'static AngularVelocity,
+            &'static Transform,
+            Option<&'static HeldBy>,
         ),
     >,
     map_q: Query<'w, 's, (Entity, &'static Map, &'static ChildOf)>,
     corner_q: Query<'w, 's, &'static Corner>,
     wall_q: Query<'w, 's, (&'static Wall, Has<Door>)>,
-    room_q: Query<'w, 's, &'static Room>,
+    room_q: Query<'w, 's, (&'static Room, Option<&'static Designation>)>,
+    route_q: Query<'w, 's, (Entity, &'static PatrolRoute, &'static ChildOf)>,
+    regime: Res<'w, Regime>,
+    clock: Res<'w, Clock>,
+    statistics: Res<'w, Statistics>,
+    seed: Option<Res<'w, RngSeed>>,
+}
+
+#[derive(QueryData)]
+struct PawnQuery {
+    id: Entity,
+    pawn: &'static Pawn,
+    parent: &'static ChildOf,
+    position: &'static Position,
+    rotation: &'static Rotation,
+    linear_velocity: &'static LinearVelocity,
+    angular_velocity: &'static AngularVelocity,
+    role: RoleQuery,
+    health: &'static Health,
+    movement: &'static MovementStats,
+    mood: &'static Mood,
+    escaping: Option<&'static Escaping>,
+    patrol: Option<&'static Patrolling>,
+}
+
+#[derive(QueryData)]
+struct TaskQuery {
+    id: Entity,
+    task: &'static Task,
+    path: Option<(
+        &'static PathTask,
+        &'static PathTarget,
+        &'static PathProgress,
+        Option<&'static GroupTask>,
+    )>,
+    interaction: Option<&'static InteractionTask>,
+    pick_up: Option<&'static PickUpTask>,
+    drop: Option<&'static DropTask>,
+    room_search: Option<&'static RoomSearchTask>,
+    reservation: Option<&'static Reservation>,
 }
 
+/// Renumbers the entities in a save densely, in the order they are added, so that saving the same
+/// state always produces the same document.
+#[derive(Default)]
+struct SaveIds(EntityHashMap<Entity>);
+
 #[derive(Debug, Serialize, Deserialize, TypePath)]
 pub struct SaveModel {
     pub pawns: Vec<PawnModel>,
     pub maps: Vec<MapModel>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<ItemModel>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupModel>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tasks: Vec<TaskModel>,
+    #[serde(default)]
+    pub regime: Regime,
+    #[serde(default)]
+    pub clock: Clock,
+    #[serde(default)]
+    pub statistics: Statistics,
+    /// The seed for random numbers when the save is loaded in [deterministic](Deterministic) mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// A summary of a save, stored alongside it so the saves menu can show it without loading the whole
+/// save.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, TypePath)]
+#[serde(default)]
+pub struct SaveInfo {
+    pub clock: Clock,
+    pub pawns: usize,
+}
+
+/// A save document, tagged with the version of the save format.
+///
+/// Documents written by older versions are upgraded to the current format when deserialized.
+#[derive(Debug, TypePath)]
+pub struct VersionedSave(pub SaveModel);
+
+#[derive(Serialize)]
+struct VersionedSaveRef<'a> {
+    version: u32,
+    save: &'a SaveModel,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,6 +166,77 @@ pub struct PawnModel {
     pub rotation: f32,
     pub linear_velocity: Vec2,
     pub angular_velocity: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<RoleModel>,
+    #[serde(default)]
+    pub health: Health,
+    #[serde(default)]
+    pub movement: MovementStats,
+    /// The movement the pawn is currently attempting.
+    #[serde(default)]
+    pub input: Pawn,
+    #[serde(default)]
+    pub mood: Mood,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escaping: Option<Escaping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patrol: Option<Patrolling>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemModel {
+    pub id: Entity,
+    pub item: Item,
+    /// The position of the item, if it is lying on the floor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Vec2>,
+    /// The pawn carrying the item, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holder: Option<Entity>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupModel {
+    pub id: Entity,
+    pub destination: Vec2,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskModel {
+    pub id: Entity,
+    pub actor: Entity,
+    pub kind: TaskKindModel,
+    /// The usage slot held by the task, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reservation: Option<Reservation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKindModel {
+    Path(PathModel),
+    Interaction(InteractionTask),
+    PickUp(PickUpTask),
+    Drop(DropTask),
+    RoomSearch(RoomSearchTask),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PathModel {
+    pub steps: VecDeque<Vec2>,
+    pub target: Vec2,
+    pub progress: PathProgress,
+    /// The group the task belongs to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<Entity>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoleModel {
+    Prisoner(Prisoner),
+    Guard(Guard),
+    Worker(Worker),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -56,6 +245,14 @@ pub struct MapModel {
     pub corners: Vec<CornerModel>,
     pub walls: Vec<WallModel>,
     pub rooms: Vec<RoomModel>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patrol_routes: Vec<PatrolRouteModel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PatrolRouteModel {
+    pub id: Entity,
+    pub route: PatrolRoute,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -76,6 +273,8 @@ pub struct WallModel {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RoomModel {
     pub id: Entity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub designation: Option<Designation>,
 }
 
 impl SaveParam<'_, '_> {
@@ -84,84 +283,388 @@ impl SaveParam<'_, '_> {
             return Err("no active game".into());
         };
 
-        let pawns = self
+        // Each list is sorted by entity index, which matches the order entities are spawned in
+        // when the save is loaded, so that saving again produces the same ids.
+        let mut ids = SaveIds::default();
+
+        let mut pawns: Vec<_> = self
             .pawn_q
             .iter()
-            .filter(|(_, _, parent, _, _, _, _)| parent.parent() == root)
-            .map(
-                |(id, _, _, position, rotation, linear_velocity, angular_velocity)| PawnModel {
-                    id,
-                    position: position.0,
-                    rotation: rotation.as_radians(),
-                    linear_velocity: linear_velocity.0,
-                    angular_velocity: angular_velocity.0,
-                },
-            )
+            .filter(|pawn| pawn.parent.parent() == root)
             .collect();
-        let maps = self
+        pawns.sort_by_key(|pawn| pawn.id.index());
+        for pawn in &pawns {
+            ids.add(pawn.id);
+        }
+
+        let mut maps: Vec<_> = self
             .map_q
             .iter()
             .filter(|(_, _, parent)| parent.parent() == root)
-            .map(|(id, map, _)| {
-                let corners = map
-                    .corners()
-                    .map(|id| {
-                        let corner = self.corner_q.get(id.id())?;
-                        Ok(CornerModel {
-                            id: id.id(),
-                            position: corner.position(),
-                        })
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-                let walls = map
-                    .walls()
-                    .map(|id| {
-                        let (wall, door) = self.wall_q.get(id.id())?;
-                        Ok(WallModel {
-                            id: id.id(),
-                            corners: wall.corners(),
-                            rooms: map.wall_rooms(wall),
-                            door,
-                        })
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-                let rooms = map
-                    .rooms_deduped()
-                    .map(|id| {
-                        let _room = self.room_q.get(id.id())?;
-                        Ok(RoomModel { id: id.id() })
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-
-                Ok(MapModel {
-                    id,
-                    corners,
-                    walls,
-                    rooms,
+            .collect();
+        maps.sort_by_key(|(id, _, _)| id.index());
+        let maps = maps
+            .into_iter()
+            .map(|(id, map, _)| self.save_map(&mut ids, id, map))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut items: Vec<_> = self
+            .item_q
+            .iter()
+            .filter(|(_, _, parent, _, _)| parent.parent() == root)
+            .collect();
+        items.sort_by_key(|(id, _, _, _, _)| id.index());
+        for &(id, _, _, _, _) in &items {
+            ids.add(id);
+        }
+
+        let mut tasks: Vec<_> = self
+            .task_q
+            .iter()
+            .filter(|task| ids.contains(task.task.actor()))
+            .filter_map(|task| {
+                Some((
+                    task.id,
+                    task.task.actor(),
+                    TaskKindModel::from_query(&task)?,
+                    task.reservation.copied(),
+                ))
+            })
+            .collect();
+        tasks.sort_by_key(|(id, _, _, _)| id.index());
+
+        let mut groups: Vec<_> = tasks
+            .iter()
+            .filter_map(|(_, _, kind, _)| match kind {
+                TaskKindModel::Path(PathModel {
+                    group: Some(group), ..
+                }) => Some((*group, self.group_q.get(*group).ok()?)),
+                _ => None,
+            })
+            .collect();
+        groups.sort_by_key(|(id, _)| id.index());
+        groups.dedup_by_key(|(id, _)| *id);
+        for &(id, _) in &groups {
+            ids.add(id);
+        }
+        for &(id, _, _, _) in &tasks {
+            ids.add(id);
+        }
+
+        let pawns = pawns
+            .into_iter()
+            .map(|pawn| PawnModel {
+                id: ids.get(pawn.id),
+                position: pawn.position.0,
+                rotation: pawn.rotation.as_radians(),
+                linear_velocity: pawn.linear_velocity.0,
+                angular_velocity: pawn.angular_velocity.0,
+                role: RoleModel::from_query(&pawn.role),
+                health: *pawn.health,
+                movement: *pawn.movement,
+                input: *pawn.pawn,
+                mood: *pawn.mood,
+                escaping: pawn.escaping.copied(),
+                patrol: pawn.patrol.map(|&patrol| ids.map(patrol)),
+            })
+            .collect();
+        let items = items
+            .into_iter()
+            .map(|(id, &item, _, transform, holder)| ItemModel {
+                id: ids.get(id),
+                item,
+                position: holder.is_none().then(|| transform.translation.xy()),
+                holder: holder.map(|holder| ids.get(holder.0)),
+            })
+            .collect();
+        let groups = groups
+            .into_iter()
+            .map(|(id, group)| GroupModel {
+                id: ids.get(id),
+                destination: group.destination,
+            })
+            .collect();
+        let tasks = tasks
+            .into_iter()
+            .map(|(id, actor, kind, reservation)| TaskModel {
+                id: ids.get(id),
+                actor: ids.get(actor),
+                kind: ids.map(kind),
+                reservation: reservation.map(|reservation| ids.map(reservation)),
+            })
+            .collect();
+
+        Ok(SaveModel {
+            pawns,
+            maps,
+            items,
+            groups,
+            tasks,
+            regime: self.regime.clone(),
+            clock: self.clock.clone(),
+            statistics: self.statistics.clone(),
+            seed: self.seed.as_deref().map(|seed| seed.0),
+        })
+    }
+
+    fn save_map(&self, ids: &mut SaveIds, id: Entity, map: &Map) -> Result<MapModel> {
+        ids.add(id);
+
+        let corners = map
+            .corners()
+            .map(|corner| {
+                let position = self.corner_q.get(corner.id())?.position();
+                Ok(CornerModel {
+                    id: ids.add(corner.id()),
+                    position,
                 })
             })
             .collect::<Result<Vec<_>>>()?;
 
-        Ok(SaveModel { pawns, maps })
+        // The order of edges in the triangulation is not preserved when a map is loaded, so walls
+        // are ordered by their corners instead, and each wall is directed from its lowest corner.
+        let mut walls = map
+            .walls()
+            .map(|wall| {
+                let (data, door) = self.wall_q.get(wall.id())?;
+                let mut corners = data.corners().map(|corner| ids.get(corner));
+                let mut rooms = map.wall_rooms(data);
+                if corners[0].index() > corners[1].index() {
+                    corners.reverse();
+                    rooms.reverse();
+                }
+                Ok((wall.id(), corners, rooms, door))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        walls.sort_by_key(|&(_, corners, _, _)| corners.map(Entity::index));
+
+        // The perimeter room must come first.
+        let mut rooms = Vec::new();
+        for room in iter::once(map.perimeter_room().id())
+            .chain(walls.iter().flat_map(|&(_, _, rooms, _)| rooms))
+            .chain(map.rooms_deduped().map(|room| room.id()))
+        {
+            if ids.contains(room) {
+                continue;
+            }
+
+            let (_, designation) = self.room_q.get(room)?;
+            rooms.push(RoomModel {
+                id: ids.add(room),
+                designation: designation.copied(),
+            });
+        }
+
+        let walls = walls
+            .into_iter()
+            .map(|(wall, corners, rooms, door)| WallModel {
+                id: ids.add(wall),
+                corners,
+                rooms: rooms.map(|room| ids.get(room)),
+                door,
+            })
+            .collect();
+
+        let mut patrol_routes: Vec<_> = self
+            .route_q
+            .iter()
+            .filter(|(_, _, parent)| parent.parent() == id)
+            .collect();
+        patrol_routes.sort_by_key(|(route, _, _)| route.index());
+        let patrol_routes = patrol_routes
+            .into_iter()
+            .map(|(route_id, route, _)| PatrolRouteModel {
+                id: ids.add(route_id),
+                route: route.clone(),
+            })
+            .collect();
+
+        Ok(MapModel {
+            id: ids.get(id),
+            corners,
+            walls,
+            rooms,
+            patrol_routes,
+        })
+    }
+}
+
+impl SaveIds {
+    fn add(&mut self, entity: Entity) -> Entity {
+        let id = Entity::from_raw(self.0.len() as u32);
+        *self.0.entry(entity).or_insert(id)
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.0.contains_key(&entity)
+    }
+
+    /// References to entities which are not part of the save are replaced with
+    /// [`Entity::PLACEHOLDER`].
+    fn get(&self, entity: Entity) -> Entity {
+        self.0.get(&entity).copied().unwrap_or(Entity::PLACEHOLDER)
+    }
+
+    fn map<T: MapEntities>(&mut self, mut value: T) -> T {
+        value.map_entities(self);
+        value
+    }
+}
+
+impl EntityMapper for SaveIds {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        self.get(source)
+    }
+
+    fn set_mapped(&mut self, source: Entity, target: Entity) {
+        self.0.insert(source, target);
+    }
+}
+
+impl Serialize for VersionedSave {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        VersionedSaveRef {
+            version: migrate::CURRENT_VERSION,
+            save: &self.0,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionedSave {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = serde_json::Value::deserialize(deserializer)?;
+        migrate::load(document)
+            .map(VersionedSave)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl RoleModel {
+    fn from_query(role: &RoleQueryItem) -> Option<Self> {
+        if let Some(&prisoner) = role.prisoner {
+            Some(RoleModel::Prisoner(prisoner))
+        } else if let Some(&guard) = role.guard {
+            Some(RoleModel::Guard(guard))
+        } else {
+            role.worker.map(|&worker| RoleModel::Worker(worker))
+        }
+    }
+
+    fn insert(&self, entity: &mut EntityWorldMut) {
+        match *self {
+            RoleModel::Prisoner(prisoner) => entity.insert(prisoner),
+            RoleModel::Guard(guard) => entity.insert(guard),
+            RoleModel::Worker(worker) => entity.insert(worker),
+        };
+    }
+}
+
+impl TaskKindModel {
+    fn from_query(task: &TaskQueryItem) -> Option<Self> {
+        if let Some((path, target, progress, group)) = task.path {
+            Some(TaskKindModel::Path(PathModel {
+                // A path which is still being computed is saved as a direct move to its target.
+                steps: path
+                    .steps()
+                    .cloned()
+                    .unwrap_or_else(|| VecDeque::from([target.0])),
+                target: target.0,
+                progress: progress.clone(),
+                group: group.map(GroupTask::group),
+            }))
+        } else if let Some(task) = task.interaction {
+            Some(TaskKindModel::Interaction(task.clone()))
+        } else if let Some(task) = task.pick_up {
+            Some(TaskKindModel::PickUp(task.clone()))
+        } else if let Some(task) = task.drop {
+            Some(TaskKindModel::Drop(task.clone()))
+        } else {
+            task.room_search
+                .map(|task| TaskKindModel::RoomSearch(task.clone()))
+        }
+    }
+
+    fn insert(self, entity: &mut EntityWorldMut) {
+        match self {
+            TaskKindModel::Path(path) => {
+                entity.insert((
+                    PathTask::Running(path.steps),
+                    PathTarget(path.target),
+                    path.progress,
+                ));
+                if let Some(group) = path.group {
+                    entity.insert(GroupTask::new(group));
+                }
+            }
+            TaskKindModel::Interaction(task) => {
+                entity.insert(task);
+            }
+            TaskKindModel::PickUp(task) => {
+                entity.insert(task);
+            }
+            TaskKindModel::Drop(task) => {
+                entity.insert(task);
+            }
+            TaskKindModel::RoomSearch(task) => {
+                entity.insert(task);
+            }
+        }
+    }
+}
+
+impl MapEntities for TaskKindModel {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        match self {
+            TaskKindModel::Path(path) => {
+                path.group = path.group.map(|group| entity_mapper.get_mapped(group));
+            }
+            TaskKindModel::Interaction(task) => task.map_entities(entity_mapper),
+            TaskKindModel::PickUp(task) => task.map_entities(entity_mapper),
+            TaskKindModel::Drop(task) => task.map_entities(entity_mapper),
+            TaskKindModel::RoomSearch(task) => task.map_entities(entity_mapper),
+        }
     }
 }
 
 impl SaveModel {
+    pub fn info(&self) -> SaveInfo {
+        SaveInfo {
+            clock: self.clock.clone(),
+            pawns: self.pawns.len(),
+        }
+    }
+
     pub fn spawn(self, commands: &mut Commands) -> Entity {
         let root = commands.spawn(Root).id();
 
         commands.queue(move |world: &mut World| -> Result {
+            if world.contains_resource::<Deterministic>() {
+                world.insert_resource(RngSeed(self.seed.unwrap_or_default()));
+            }
+
             let mut entity_map = EntityHashMap::<Entity>::new();
 
-            for (entity, pawn) in world
-                .spawn_batch(
-                    self.pawns
-                        .iter()
-                        .map(|pawn| (PawnBundle::new(pawn.position, pawn.rotation), ChildOf(root))),
-                )
-                .zip(&self.pawns)
-            {
-                entity_map.insert(pawn.id, entity);
+            for pawn in &self.pawns {
+                let mut entity = world.spawn(ChildOf(root));
+                // Insert the role first so it is visible to observers of the pawn being added.
+                if let Some(role) = &pawn.role {
+                    role.insert(&mut entity);
+                }
+                entity
+                    .insert((
+                        PawnBundle::new(pawn.position, pawn.rotation),
+                        LinearVelocity(pawn.linear_velocity),
+                        AngularVelocity(pawn.angular_velocity),
+                        pawn.health,
+                        pawn.movement,
+                        pawn.mood,
+                    ))
+                    .insert(pawn.input);
+                if let Some(escaping) = pawn.escaping {
+                    entity.insert(escaping);
+                }
+                entity_map.insert(pawn.id, entity.id());
             }
 
             for map in &self.maps {
@@ -199,8 +702,73 @@ impl SaveModel {
                             .insert(Door);
                     }
                 }
+
+                for room in &map.rooms {
+                    if let Some(designation) = room.designation {
+                        world
+                            .entity_mut(entity_map.get_mapped(room.id))
+                            .insert(designation);
+                    }
+                }
+
+                for route in &map.patrol_routes {
+                    let entity = world.spawn((route.route.clone(), ChildOf(map_id)));
+                    entity_map.insert(route.id, entity.id());
+                }
+            }
+
+            for pawn in &self.pawns {
+                if let Some(mut patrol) = pawn.patrol {
+                    patrol.map_entities(&mut entity_map);
+                    world
+                        .entity_mut(entity_map.get_mapped(pawn.id))
+                        .insert(patrol);
+                }
+            }
+
+            for item in &self.items {
+                let mut entity = world.spawn((
+                    item.item.bundle(item.position.unwrap_or_default()),
+                    ChildOf(root),
+                ));
+                if let Some(holder) = item.holder {
+                    entity.insert(HeldBy(entity_map.get_mapped(holder)));
+                }
+                entity_map.insert(item.id, entity.id());
+            }
+
+            for group in &self.groups {
+                let entity = world.spawn(MoveGroup {
+                    destination: group.destination,
+                });
+                entity_map.insert(group.id, entity.id());
+            }
+
+            // Interaction tasks refer to each other, so every task is spawned before any are filled in.
+            for task in &self.tasks {
+                entity_map.insert(task.id, world.spawn_empty().id());
+            }
+            for mut task in self.tasks {
+                task.kind.map_entities(&mut entity_map);
+                let id = entity_map.get_mapped(task.id);
+                let actor = entity_map.get_mapped(task.actor);
+                if let Some(mut reservation) = task.reservation {
+                    reservation.map_entities(&mut entity_map);
+                    if let Some(mut slots) = world.get_mut::<UsageSlots>(reservation.object()) {
+                        slots.restore(&reservation, id);
+                    }
+                    world.entity_mut(id).insert(reservation);
+                }
+                let mut entity = world.entity_mut(id);
+                // Insert the task last so its observers see the complete task.
+                task.kind.insert(&mut entity);
+                entity.insert(Task::new(actor));
             }
 
+            world.insert_resource(self.regime);
+            world.insert_resource(self.clock);
+            world.insert_resource(self.statistics);
+
             Ok(())
         });
 
@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+use crate::item::ItemKind;
+
+/// A notable event related to the security of the prison.
+#[derive(Debug, Clone, Copy, Event)]
+pub enum SecurityEvent {
+    /// A prisoner has started an escape attempt.
+    EscapeStarted { prisoner: Entity, number: u32 },
+    /// A guard has seen a prisoner attempting to escape, foiling the attempt.
+    EscapeSpotted {
+        prisoner: Entity,
+        number: u32,
+        guard: Entity,
+    },
+    /// A prisoner has reached the perimeter and escaped.
+    Escaped { prisoner: Entity, number: u32 },
+    /// A guard has confiscated contraband, either from a pawn or from the floor of a room.
+    ContrabandFound {
+        guard: Entity,
+        kind: ItemKind,
+        owner: Option<Entity>,
+    },
+}
@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{root::Root, security::SecurityEvent};
+
+/// Running totals of notable events over the course of a game.
+#[derive(Debug, Default, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    pub escape_attempts: u32,
+    pub escapes_spotted: u32,
+    pub escapes: u32,
+    pub contraband_found: u32,
+}
+
+pub fn update(mut statistics: ResMut<Statistics>, mut security_e: EventReader<SecurityEvent>) {
+    for event in security_e.read() {
+        match event {
+            SecurityEvent::EscapeStarted { .. } => statistics.escape_attempts += 1,
+            SecurityEvent::EscapeSpotted { .. } => statistics.escapes_spotted += 1,
+            SecurityEvent::Escaped { .. } => statistics.escapes += 1,
+            SecurityEvent::ContrabandFound { .. } => statistics.contraband_found += 1,
+        }
+    }
+}
+
+pub fn root_added(_: Trigger<OnAdd, Root>, mut commands: Commands) {
+    commands.insert_resource(Statistics::default());
+}
@@ -0,0 +1,74 @@
+use std::{fs, path::Path};
+
+use bevy::{
+    app::{TaskPoolOptions, TaskPoolPlugin},
+    ecs::world::CommandQueue,
+    prelude::*,
+    scene::ScenePlugin,
+    state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+};
+use pb_engine::{
+    EngineState, PbEnginePlugin,
+    determinism::Deterministic,
+    save::{SaveParam, VersionedSave, migrate::CURRENT_VERSION},
+};
+
+const TICKS: usize = 1000;
+
+/// Runs the same save in two games, checking they are in exactly the same state afterwards.
+#[test]
+fn deterministic() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(format!("tests/data/saves/v{CURRENT_VERSION}/full.json"));
+    let document = fs::read_to_string(path).unwrap();
+
+    let initial = run(&document, 0);
+    let first = run(&document, TICKS);
+    let second = run(&document, TICKS);
+
+    // The saves are large, so avoid printing them on failure.
+    assert!(first != initial, "simulation did not change the save");
+    assert!(first == second, "saves differ after {TICKS} ticks");
+}
+
+/// Loads a save into a new deterministic game and runs it for a number of fixed timesteps, then
+/// saves it again.
+fn run(document: &str, ticks: usize) -> Vec<u8> {
+    let mut app = App::new();
+    app.insert_resource(Deterministic);
+    app.add_plugins((
+        MinimalPlugins.set(TaskPoolPlugin {
+            task_pool_options: TaskPoolOptions::with_num_threads(1),
+        }),
+        TransformPlugin,
+        AssetPlugin::default(),
+        StatesPlugin,
+        ScenePlugin,
+        PbEnginePlugin,
+    ));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        Time::<Fixed>::default().timestep(),
+    ));
+    app.finish();
+    app.cleanup();
+    app.update();
+
+    let VersionedSave(save) = serde_json::from_str(document).unwrap();
+    let world = app.world_mut();
+    let mut commands = CommandQueue::default();
+    let root = save.spawn(&mut Commands::new(&mut commands, world));
+    commands.apply(world);
+    world.insert_resource(State::new(EngineState::Running(root)));
+
+    for _ in 0..ticks {
+        app.update();
+    }
+
+    let save = app
+        .world_mut()
+        .run_system_cached(|save_p: SaveParam| save_p.save())
+        .unwrap()
+        .unwrap();
+    serde_json::to_vec(&VersionedSave(save)).unwrap()
+}
@@ -0,0 +1,112 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{ecs::world::CommandQueue, prelude::*, scene::ScenePlugin, state::app::StatesPlugin};
+use pb_engine::{
+    EngineState, PbEnginePlugin,
+    pawn::ai::reservation::{Reservation, UsageSlots},
+    save::{SaveParam, VersionedSave, migrate::CURRENT_VERSION},
+};
+use serde_json::Value;
+
+/// Loads every save in the corpus, which keeps at least one document for each version of the save
+/// format, and checks it upgrades to a stable document in the current format.
+#[test]
+fn golden_saves() {
+    let dir = saves_dir();
+
+    for version in 0..=CURRENT_VERSION {
+        let version_dir = dir.join(format!("v{version}"));
+        let entries = fs::read_dir(&version_dir)
+            .unwrap_or_else(|error| panic!("no saves for version {version}: {error}"));
+
+        for entry in entries {
+            let path = entry.unwrap().path();
+            let json = fs::read_to_string(&path).unwrap();
+            let save: VersionedSave = serde_json::from_str(&json)
+                .unwrap_or_else(|error| panic!("failed to load '{}': {error}", path.display()));
+
+            let upgraded = serde_json::to_value(&save).unwrap();
+            assert_eq!(upgraded["version"], CURRENT_VERSION);
+
+            let reloaded: VersionedSave = serde_json::from_value(upgraded.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&reloaded).unwrap(), upgraded);
+        }
+    }
+}
+
+/// Loads a save into a game and saves it, then does the same with the result, checking that no
+/// state is lost and that the second save is identical to the first.
+#[test]
+fn round_trip() {
+    let path = saves_dir().join(format!("v{CURRENT_VERSION}/full.json"));
+    let original: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+
+    let first = resave(original.clone());
+    for field in ["pawns", "items", "groups", "tasks"] {
+        assert_eq!(
+            first["save"][field].as_array().map(Vec::len),
+            original["save"][field].as_array().map(Vec::len),
+            "{field} were not saved",
+        );
+    }
+    assert_eq!(
+        reservations(&first),
+        reservations(&original),
+        "reservations were not saved",
+    );
+
+    let second = resave(first.clone());
+    assert_eq!(first, second);
+}
+
+fn reservations(document: &Value) -> usize {
+    document["save"]["tasks"].as_array().map_or(0, |tasks| {
+        tasks
+            .iter()
+            .filter(|task| task.get("reservation").is_some())
+            .count()
+    })
+}
+
+fn saves_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/saves")
+}
+
+/// Spawns a save document into a new game and saves it again, without running the simulation.
+fn resave(document: Value) -> Value {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        AssetPlugin::default(),
+        StatesPlugin,
+        ScenePlugin,
+        PbEnginePlugin,
+    ));
+    app.finish();
+    app.cleanup();
+    app.update();
+
+    let VersionedSave(save) = serde_json::from_value(document).unwrap();
+    let world = app.world_mut();
+    let mut commands = CommandQueue::default();
+    let root = save.spawn(&mut Commands::new(&mut commands, world));
+    commands.apply(world);
+    world.insert_resource(State::new(EngineState::Running(root)));
+
+    // Reserved slots must be held by their tasks, or other pawns could take them.
+    let mut reservation_q = world.query::<(Entity, &Reservation)>();
+    for (task, reservation) in reservation_q.iter(world) {
+        let slots = world.get::<UsageSlots>(reservation.object()).unwrap();
+        assert_eq!(slots.slots()[reservation.slot()].reserved_by(), Some(task));
+    }
+
+    let save = world
+        .run_system_cached(|save_p: SaveParam| save_p.save())
+        .unwrap()
+        .unwrap();
+    serde_json::to_value(VersionedSave(save)).unwrap()
+}
@@ -1,6 +1,10 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
-use std::{collections::VecDeque, f32::consts::PI, time::Duration};
+use std::{
+    collections::VecDeque,
+    f32::consts::{PI, TAU},
+    time::Duration,
+};
 
 use avian2d::prelude::*;
 use bevy::{
@@ -13,7 +17,7 @@ use bevy::{
 use pb_engine::{
     PbEnginePlugin,
     pawn::{
-        Pawn, PawnBundle,
+        MovementStats, PawnBundle,
         ai::path::{MovementQuery, PathObservation},
     },
     save::SaveModel,
@@ -32,6 +36,7 @@ pub struct Environment {
     app: App,
     rng: SmallRng,
     entity: Entity,
+    stats: MovementStats,
     path_q: SystemState<MovementQuery<'static, 'static>>,
     step_count: usize,
     path: VecDeque<Vec2>,
@@ -96,6 +101,7 @@ impl Environment {
             app,
             rng: SmallRng::from_os_rng(),
             entity: Entity::PLACEHOLDER,
+            stats: MovementStats::DEFAULT,
             path_q,
             step_count: 0,
             path: VecDeque::new(),
@@ -129,15 +135,25 @@ impl Environment {
 
         let rotation = self.rng.random_range(-PI..PI);
 
+        // Randomise the limits so the policy generalises to pawns of all speeds.
+        self.stats = MovementStats {
+            max_acceleration: self.rng.random_range(0.4..1.0),
+            max_velocity: self.rng.random_range(0.5..2.0),
+            max_torque: self.rng.random_range(0.75..1.25) * TAU,
+            max_angular_velocity: self.rng.random_range(0.75..1.25) * PI,
+        };
+
         let linear_velocity_angle = self.rng.random_range(-PI..PI);
-        let max_velocity =
-            Pawn::MAX_VELOCITY.lerp(Pawn::MAX_VELOCITY / 2., linear_velocity_angle.abs() / PI);
+        let max_velocity = self.stats.max_velocity.lerp(
+            self.stats.max_velocity / 2.,
+            linear_velocity_angle.abs() / PI,
+        );
         let linear_velocity = Vec2::from_angle(rotation + linear_velocity_angle)
             * self.rng.random_range(0.0..max_velocity);
 
-        let max_angular_velocity = Pawn::MAX_ANGULAR_VELOCITY.lerp(
-            Pawn::MAX_ANGULAR_VELOCITY / 2.,
-            linear_velocity.length() / Pawn::MAX_VELOCITY,
+        let max_angular_velocity = self.stats.max_angular_velocity.lerp(
+            self.stats.max_angular_velocity / 2.,
+            linear_velocity.length() / self.stats.max_velocity,
         );
         let angular_velocity = self
             .rng
@@ -148,6 +164,7 @@ impl Environment {
             .world_mut()
             .spawn((
                 PawnBundle::new(position, rotation),
+                self.stats,
                 LinearVelocity(linear_velocity),
                 AngularVelocity(angular_velocity),
             ))
@@ -179,7 +196,7 @@ impl Environment {
             self.rng.random_range(3.0..6.0) * steps_completed as f32
         } else {
             (prev_observation.target_r - observation.target_r)
-                / (Pawn::MAX_VELOCITY * TIMESTEP.as_secs_f32())
+                / (self.stats.max_velocity * TIMESTEP.as_secs_f32())
         };
 
         let reward = dist_reward
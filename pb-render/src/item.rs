@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+use pb_engine::{
+    item::{HeldBy, Item},
+    pawn::Pawn,
+};
+
+use crate::layer;
+
+const ITEM_SPRITE_SIZE: Vec2 = Vec2::splat(Pawn::RADIUS * 0.8);
+const ITEM_COLOR: Color = Color::srgb(0.6, 0.6, 0.55);
+const CONTRABAND_COLOR: Color = Color::srgb(0.8, 0.2, 0.15);
+
+pub fn item_added(
+    trigger: Trigger<OnAdd, Item>,
+    mut commands: Commands,
+    item_q: Query<&Item>,
+) -> Result {
+    let item = item_q.get(trigger.target())?;
+    let color = if item.contraband {
+        CONTRABAND_COLOR
+    } else {
+        ITEM_COLOR
+    };
+
+    commands.spawn((
+        Transform::from_xyz(0., 0., layer::ITEM),
+        Visibility::Inherited,
+        Sprite::from_color(color, ITEM_SPRITE_SIZE),
+        ChildOf(trigger.target()),
+    ));
+    Ok(())
+}
+
+pub fn held_inserted(trigger: Trigger<OnInsert, HeldBy>, mut commands: Commands) {
+    commands
+        .entity(trigger.target())
+        .try_insert(Visibility::Hidden);
+}
+
+pub fn held_replaced(trigger: Trigger<OnReplace, HeldBy>, mut commands: Commands) {
+    commands
+        .entity(trigger.target())
+        .try_insert(Visibility::Inherited);
+}
@@ -1,5 +1,6 @@
 pub const GRID: f32 = 0.0;
 pub const WALL: f32 = 1.0;
+pub const ITEM: f32 = 1.5;
 pub const PAWN_HIGHLIGHT: f32 = 2.0;
 pub const PAWN_BODY: f32 = 2.1;
 pub const PAWN_HEAD: f32 = 2.2;
@@ -1,9 +1,11 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
 pub mod grid;
+pub mod item;
 pub mod layer;
 pub mod pawn;
 pub mod projection;
+pub mod thumbnail;
 pub mod wall;
 
 use bevy::{prelude::*, sprite::Material2dPlugin};
@@ -33,7 +35,11 @@ impl Plugin for PbRenderPlugin {
         app.add_observer(wall::corner_inserted)
             .add_observer(wall::wall_inserted)
             .add_observer(wall::map_removed)
-            .add_observer(pawn::pawn_added);
+            .add_observer(pawn::pawn_added)
+            .add_observer(pawn::role_inserted)
+            .add_observer(item::item_added)
+            .add_observer(item::held_inserted)
+            .add_observer(item::held_replaced);
 
         app.init_resource::<VisibleMaps>();
 
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
 
 use pb_assets::AssetHandles;
-use pb_engine::pawn::Pawn;
+use pb_engine::pawn::{
+    Pawn,
+    role::{Guard, Job, Prisoner, Role, RoleQuery, RoleQueryItem, Worker},
+};
 use pb_util::rng::LocalRng;
 use rand::{Rng, seq::IndexedRandom};
 
@@ -12,6 +15,9 @@ const PAWN_SPRITE_SIZE: Vec2 = Vec2::splat(Pawn::RADIUS * 4.);
 #[derive(Default, Copy, Clone, Component)]
 pub struct PawnSprite;
 
+#[derive(Default, Copy, Clone, Component)]
+pub struct PawnBodySprite;
+
 #[derive(Default, Copy, Clone, Component)]
 pub struct PawnHighlight;
 
@@ -19,12 +25,14 @@ pub fn pawn_added(
     trigger: Trigger<OnAdd, Pawn>,
     mut commands: Commands,
     assets: Res<AssetHandles>,
+    role_q: Query<RoleQuery>,
     mut rng: LocalRng,
 ) -> Result {
+    let role = role_q.get(trigger.target())?;
     let head = rng.random_range(0..7);
-    let body = rng.random_range(0..4);
+    let body = random_body(role.role(), &mut rng);
     let skin = random_skin_tone(&mut rng);
-    let uniform = random_uniform(&mut rng);
+    let uniform = uniform(&role);
 
     commands
         .entity(trigger.target())
@@ -47,6 +55,7 @@ pub fn pawn_added(
     ));
     commands.spawn((
         PawnSprite,
+        PawnBodySprite,
         Transform::from_xyz(0., 0., layer::PAWN_BODY),
         Visibility::Visible,
         Sprite {
@@ -64,6 +73,25 @@ pub fn pawn_added(
     Ok(())
 }
 
+pub fn role_inserted(
+    trigger: Trigger<OnInsert, (Prisoner, Guard, Worker)>,
+    pawn_q: Query<(RoleQuery, Option<&Children>), With<Pawn>>,
+    mut sprite_q: Query<&mut Sprite, With<PawnBodySprite>>,
+    mut rng: LocalRng,
+) {
+    let Ok((role, Some(children))) = pawn_q.get(trigger.target()) else {
+        return;
+    };
+
+    let mut sprites = sprite_q.iter_many_mut(children);
+    while let Some(mut sprite) = sprites.fetch_next() {
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            atlas.index = random_body(role.role(), &mut rng);
+        }
+        sprite.color = uniform(&role);
+    }
+}
+
 pub fn clear_rotation(
     mut sprite_q: Query<(&mut Transform, &ChildOf), With<PawnSprite>>,
     parent_q: Query<&Transform, Without<PawnSprite>>,
@@ -108,14 +136,29 @@ fn random_skin_tone(rng: &mut impl Rng) -> Color {
     SKIN_TONES.choose(rng).copied().unwrap().into()
 }
 
-fn random_uniform(rng: &mut impl Rng) -> Color {
-    static UNIFORMS: [Hsla; 5] = [
-        Hsla::hsl(194., 0.71, 0.52),
-        Hsla::hsl(39., 0.98, 0.53),
-        Hsla::hsl(14., 0.98, 0.48),
-        Hsla::hsl(71., 0.88, 0.49),
-        Hsla::hsl(291., 0.51, 0.92),
-    ];
+fn random_body(role: Option<Role>, rng: &mut impl Rng) -> usize {
+    match role {
+        Some(Role::Guard) => *[1, 2].choose(rng).unwrap(),
+        Some(Role::Worker) => *[0, 1, 3].choose(rng).unwrap(),
+        Some(Role::Prisoner) | None => rng.random_range(0..4),
+    }
+}
+
+fn uniform(role: &RoleQueryItem) -> Color {
+    let color = if role.prisoner.is_some() {
+        Hsla::hsl(24., 0.98, 0.5)
+    } else if role.guard.is_some() {
+        Hsla::hsl(214., 0.62, 0.4)
+    } else if let Some(worker) = role.worker {
+        match worker.job {
+            Job::Cook => Hsla::hsl(291., 0.51, 0.92),
+            Job::Cleaner => Hsla::hsl(194., 0.71, 0.52),
+            Job::Gardener => Hsla::hsl(71., 0.88, 0.49),
+            Job::Medic => Hsla::hsl(0., 0., 0.98),
+        }
+    } else {
+        Hsla::hsl(0., 0., 0.7)
+    };
 
-    UNIFORMS.choose(rng).copied().unwrap().into()
+    color.into()
 }
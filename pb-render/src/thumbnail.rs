@@ -0,0 +1,216 @@
+//! Small preview images of saves, drawn on the CPU from the saved geometry so they can be created
+//! while headless.
+
+use std::io::Cursor;
+
+use bevy::{asset::RenderAssetUsages, platform::collections::HashMap, prelude::*};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use pb_engine::{
+    map::wall::Wall,
+    pawn::Pawn,
+    save::{RoleModel, SaveModel},
+};
+
+pub const WIDTH: u32 = 160;
+pub const HEIGHT: u32 = 120;
+
+/// The space left around the edge of the image, in pixels.
+const PADDING: f32 = 6.;
+/// The area shown when there is nothing in the save to fit, in meters.
+const EMPTY_SIZE: f32 = 16.;
+
+const BACKGROUND_COLOR: Rgba<u8> = Rgba([0x26, 0x2b, 0x2f, 0xff]);
+const WALL_COLOR: Rgba<u8> = Rgba([0xd8, 0xd8, 0xd0, 0xff]);
+const DOOR_COLOR: Rgba<u8> = Rgba([0x9c, 0x6b, 0x3e, 0xff]);
+const PRISONER_COLOR: Rgba<u8> = Rgba([0xfc, 0x7b, 0x03, 0xff]);
+const GUARD_COLOR: Rgba<u8> = Rgba([0x27, 0x5e, 0xa5, 0xff]);
+const WORKER_COLOR: Rgba<u8> = Rgba([0x7c, 0xc8, 0x3c, 0xff]);
+const PAWN_COLOR: Rgba<u8> = Rgba([0xb3, 0xb3, 0xb3, 0xff]);
+
+/// Maps world positions to pixel coordinates, fitting a region of the world into the image.
+struct View {
+    min: Vec2,
+    offset: Vec2,
+    scale: f32,
+}
+
+/// Draws an overhead view of a save's walls, doors and pawns, encoded as PNG.
+pub fn render(save: &SaveModel) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    draw(save)
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|error| format!("failed to encode thumbnail: {error}"))?;
+    Ok(bytes)
+}
+
+/// Decodes a thumbnail created by [`render`] into an image which can be displayed in the UI.
+pub fn decode(bytes: &[u8]) -> Result<Image> {
+    let image = image::load_from_memory_with_format(bytes, ImageFormat::Png)
+        .map_err(|error| format!("failed to decode thumbnail: {error}"))?;
+    Ok(Image::from_dynamic(
+        DynamicImage::ImageRgba8(image.into_rgba8()),
+        true,
+        RenderAssetUsages::RENDER_WORLD,
+    ))
+}
+
+fn draw(save: &SaveModel) -> RgbaImage {
+    let mut image = RgbaImage::from_pixel(WIDTH, HEIGHT, BACKGROUND_COLOR);
+
+    let positions = save
+        .maps
+        .iter()
+        .flat_map(|map| map.corners.iter().map(|corner| corner.position))
+        .chain(save.pawns.iter().map(|pawn| pawn.position));
+    let view = View::fit(positions);
+
+    // Lines thinner than a pixel would leave gaps, so walls are always at least that wide.
+    let wall_radius = (view.scale * Wall::RADIUS).max(0.6);
+    for map in &save.maps {
+        let corners: HashMap<Entity, Vec2> = map
+            .corners
+            .iter()
+            .map(|corner| (corner.id, corner.position))
+            .collect();
+
+        for wall in &map.walls {
+            let (Some(&start), Some(&end)) =
+                (corners.get(&wall.corners[0]), corners.get(&wall.corners[1]))
+            else {
+                continue;
+            };
+
+            let color = if wall.door { DOOR_COLOR } else { WALL_COLOR };
+            draw_segment(
+                &mut image,
+                view.project(start),
+                view.project(end),
+                wall_radius,
+                color,
+            );
+        }
+    }
+
+    let pawn_radius = (view.scale * Pawn::RADIUS).max(1.5);
+    for pawn in &save.pawns {
+        let color = match pawn.role {
+            Some(RoleModel::Prisoner(_)) => PRISONER_COLOR,
+            Some(RoleModel::Guard(_)) => GUARD_COLOR,
+            Some(RoleModel::Worker(_)) => WORKER_COLOR,
+            None => PAWN_COLOR,
+        };
+        let center = view.project(pawn.position);
+        draw_segment(&mut image, center, center, pawn_radius, color);
+    }
+
+    image
+}
+
+/// Fills the pixels whose centers are within `radius` of a line segment.
+fn draw_segment(image: &mut RgbaImage, start: Vec2, end: Vec2, radius: f32, color: Rgba<u8>) {
+    let min = (start.min(end) - radius).floor().max(Vec2::ZERO);
+    let max = (start.max(end) + radius)
+        .ceil()
+        .min(Vec2::new(WIDTH as f32, HEIGHT as f32));
+
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    for y in min.y as u32..max.y as u32 {
+        for x in min.x as u32..max.x as u32 {
+            let point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let t = if length_squared > 0. {
+                ((point - start).dot(segment) / length_squared).clamp(0., 1.)
+            } else {
+                0.
+            };
+
+            if point.distance_squared(start + segment * t) <= radius * radius {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+impl View {
+    fn fit(positions: impl Iterator<Item = Vec2>) -> Self {
+        let (min, max) = positions.fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), position| (min.min(position), max.max(position)),
+        );
+        let (min, max) = if min.cmple(max).all() {
+            (min, max)
+        } else {
+            (Vec2::splat(-EMPTY_SIZE / 2.), Vec2::splat(EMPTY_SIZE / 2.))
+        };
+
+        let image_size = Vec2::new(WIDTH as f32, HEIGHT as f32);
+        let size = (max - min).max(Vec2::ONE);
+        let scale = ((image_size - 2. * PADDING) / size).min_element();
+
+        View {
+            min,
+            offset: (image_size - size * scale) / 2.,
+            scale,
+        }
+    }
+
+    /// Converts a world position to pixel coordinates, which have the y axis pointing down.
+    fn project(&self, position: Vec2) -> Vec2 {
+        let pixel = self.offset + (position - self.min) * self.scale;
+        Vec2::new(pixel.x, HEIGHT as f32 - pixel.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pb_engine::save::{CornerModel, MapModel, WallModel};
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let corner = |index: u32, position| CornerModel {
+            id: Entity::from_raw(index),
+            position,
+        };
+        let save = SaveModel {
+            maps: vec![MapModel {
+                id: Entity::from_raw(0),
+                corners: vec![
+                    corner(1, Vec2::new(-4., 0.)),
+                    corner(2, Vec2::new(4., 0.)),
+                    corner(3, Vec2::new(0., 3.)),
+                ],
+                walls: vec![WallModel {
+                    id: Entity::from_raw(4),
+                    corners: [Entity::from_raw(1), Entity::from_raw(2)],
+                    rooms: [Entity::from_raw(5), Entity::from_raw(5)],
+                    door: false,
+                }],
+                rooms: vec![],
+                patrol_routes: vec![],
+            }],
+            pawns: vec![],
+            items: vec![],
+            groups: vec![],
+            tasks: vec![],
+            regime: default(),
+            clock: default(),
+            statistics: default(),
+            seed: None,
+        };
+
+        let png = render(&save).unwrap();
+        let image = image::load_from_memory_with_format(&png, ImageFormat::Png)
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(image.dimensions(), (WIDTH, HEIGHT));
+
+        let view = View::fit(save.maps[0].corners.iter().map(|corner| corner.position));
+        let wall = view.project(Vec2::ZERO);
+        assert_eq!(*image.get_pixel(wall.x as u32, wall.y as u32), WALL_COLOR);
+        assert_eq!(*image.get_pixel(0, 0), BACKGROUND_COLOR);
+
+        assert_eq!(decode(&png).unwrap().width(), WIDTH);
+    }
+}
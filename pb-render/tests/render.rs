@@ -27,7 +27,7 @@ use bevy::{
 };
 
 use pb_assets::{AssetHandles, PbAssetsPlugin};
-use pb_engine::{EngineState, PbEnginePlugin, save::SaveModel};
+use pb_engine::{EngineState, PbEnginePlugin, save::VersionedSave};
 use pb_render::{
     PbRenderPlugin,
     grid::{GRID_MESH_HANDLE, GridMaterial},
@@ -174,7 +174,7 @@ fn update(
             ));
 
             let save_json = fs::read_to_string(config.dir.join("save.json"))?;
-            let save: SaveModel = serde_json::from_str(&save_json)?;
+            let VersionedSave(save) = serde_json::from_str(&save_json)?;
             let root = save.spawn(&mut commands);
             next_engine_state.set(EngineState::Running(root));
 
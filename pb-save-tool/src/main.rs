@@ -0,0 +1,384 @@
+//! Inspects and edits saves from the command line, without starting the game.
+
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process::ExitCode,
+};
+
+use bevy::{
+    ecs::entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+    tasks::block_on,
+};
+use pb_engine::{
+    map::Map,
+    save::{RoleModel, SaveModel, VersionedSave, migrate},
+};
+use pb_render::thumbnail;
+use pb_store::{Archive, Format, Sidecar, Store};
+use serde_json::Value;
+
+const USAGE: &str = "\
+Usage: pb-save-tool <command> <save> [<output>]
+
+Commands:
+  stats         Print the number of maps, rooms, walls and pawns in the save
+  validate      Check that every map in the save can be built
+  convert       Write the save to <output>, in the format given by its extension
+  migrate       Upgrade the save to the current format version, in place or to <output>
+  strip-pawns   Remove all pawns and their tasks, in place or to <output>
+
+Saves may be JSON (.json), binary (.sav) or exported archives (.pbsave).";
+
+/// A save read from a file, along with the format version it was written with.
+struct LoadedSave {
+    save: SaveModel,
+    version: u32,
+    /// The value and sidecars as read from the file, before migrating.
+    archive: Archive,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs a command, returning whether it succeeded.
+fn run(args: &[&str]) -> Result<bool> {
+    match *args {
+        ["stats", path] => {
+            print_stats(&load(path)?);
+            Ok(true)
+        }
+        ["validate", path] => {
+            let problems = validate(&load(path)?.save);
+            for problem in &problems {
+                println!("{problem}");
+            }
+            if problems.is_empty() {
+                println!("'{path}' is valid");
+            }
+            Ok(problems.is_empty())
+        }
+        ["convert", path, output] => {
+            let loaded = load(path)?;
+            write(output, loaded.save, &loaded.archive)?;
+            println!("Converted '{path}' to '{output}'");
+            Ok(true)
+        }
+        ["migrate", path] | ["migrate", path, _] => {
+            let output = args.get(2).copied().unwrap_or(path);
+            let loaded = load(path)?;
+            write(output, loaded.save, &loaded.archive)?;
+            println!(
+                "Upgraded '{path}' from format version {} to {}",
+                loaded.version,
+                migrate::CURRENT_VERSION
+            );
+            Ok(true)
+        }
+        ["strip-pawns", path] | ["strip-pawns", path, _] => {
+            let output = args.get(2).copied().unwrap_or(path);
+            let mut loaded = load(path)?;
+            let count = strip_pawns(&mut loaded.save);
+            write(output, loaded.save, &loaded.archive)?;
+            println!("Removed {count} pawns from '{path}'");
+            Ok(true)
+        }
+        ["help" | "--help" | "-h"] => {
+            println!("{USAGE}");
+            Ok(true)
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            Ok(false)
+        }
+    }
+}
+
+fn load(path: &str) -> Result<LoadedSave> {
+    let path = Path::new(path);
+
+    // A plain save is bundled with any sidecars stored next to it.
+    let archive = if is_archive(path) {
+        let bytes = fs::read(path)
+            .map_err(|error| format!("failed to read '{}': {error}", path.display()))?;
+        Archive::from_bytes(&bytes)?
+    } else {
+        let (store, key) = open(path)?;
+        block_on(store.export(&key))?
+    };
+    let document: Value = archive.decode()?;
+
+    let version = match document.get("version") {
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| format!("unrecognized version '{version}'"))?,
+        None => 0,
+    };
+    let save = migrate::load(document)?;
+
+    Ok(LoadedSave {
+        save,
+        version,
+        archive,
+    })
+}
+
+/// Writes a save in the current format version. If the save has info or thumbnail sidecars, they
+/// are updated to match.
+///
+/// Archives are written with the sidecars of the archive the save was loaded from, and keep the
+/// format of its value.
+fn write(path: &str, save: SaveModel, source: &Archive) -> Result {
+    let path = Path::new(path);
+    if !is_archive(path) {
+        let (store, key) = open(path)?;
+        return block_on(set_save(&store, &key, save));
+    }
+
+    let key = match source.format() {
+        Format::Json => "save.json",
+        Format::Binary => "save.sav",
+    };
+    let store = Store::memory();
+    let archive = block_on(async {
+        store.import(key, source.clone()).await?;
+        set_save(&store, key, save).await?;
+        store.export(key).await
+    })?;
+
+    fs::write(path, archive.to_bytes())
+        .map_err(|error| format!("failed to write '{}': {error}", path.display()).into())
+}
+
+async fn set_save(store: &Store, key: &str, save: SaveModel) -> Result {
+    let info = save.info();
+    let thumbnail_key = Sidecar::Thumbnail.key(key);
+    let thumbnail = match store.try_get_bytes(&thumbnail_key).await? {
+        Some(_) => Some(thumbnail::render(&save)?),
+        None => None,
+    };
+    store.set(key, VersionedSave(save)).await?;
+
+    let info_key = Sidecar::Info.key(key);
+    if store.try_get_bytes(&info_key).await?.is_some() {
+        store.set(&info_key, info).await?;
+    }
+    if let Some(thumbnail) = thumbnail {
+        store.set_bytes(&thumbnail_key, thumbnail).await?;
+    }
+    Ok(())
+}
+
+fn is_archive(path: &Path) -> bool {
+    path.extension() == Some(OsStr::new(Archive::EXTENSION))
+}
+
+/// Opens the directory containing a file as a store, returning the key of the file within it.
+fn open(path: &Path) -> Result<(Store, String)> {
+    let name = path
+        .file_name()
+        .and_then(OsStr::to_str)
+        .ok_or_else(|| format!("invalid path '{}'", path.display()))?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    Ok((Store::dir(dir), name.to_owned()))
+}
+
+fn print_stats(loaded: &LoadedSave) {
+    let save = &loaded.save;
+
+    let format = match loaded.archive.format() {
+        Format::Json => "JSON",
+        Format::Binary => "binary",
+    };
+    println!("format: {format}, version {}", loaded.version);
+    println!("clock: {}", save.clock);
+
+    println!("maps: {}", save.maps.len());
+    for (index, map) in save.maps.iter().enumerate() {
+        let doors = map.walls.iter().filter(|wall| wall.door).count();
+        println!(
+            "  map {index}: {} corners, {} walls ({doors} doors), {} rooms, {} patrol routes",
+            map.corners.len(),
+            map.walls.len(),
+            map.rooms.len(),
+            map.patrol_routes.len(),
+        );
+    }
+
+    let count = |f: fn(&RoleModel) -> bool| {
+        save.pawns
+            .iter()
+            .filter(|pawn| pawn.role.as_ref().is_some_and(f))
+            .count()
+    };
+    println!(
+        "pawns: {} ({} prisoners, {} guards, {} workers)",
+        save.pawns.len(),
+        count(|role| matches!(role, RoleModel::Prisoner(_))),
+        count(|role| matches!(role, RoleModel::Guard(_))),
+        count(|role| matches!(role, RoleModel::Worker(_))),
+    );
+    println!("items: {}", save.items.len());
+    println!("groups: {}", save.groups.len());
+    println!("tasks: {}", save.tasks.len());
+}
+
+/// Checks each map can be built the same way as when the save is loaded, and that entities refer
+/// to each other consistently. Returns a description of each problem found.
+fn validate(save: &SaveModel) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for (index, map) in save.maps.iter().enumerate() {
+        let rooms: EntityHashSet = map.rooms.iter().map(|room| room.id).collect();
+        for wall in &map.walls {
+            for room in wall.rooms {
+                if !rooms.contains(&room) {
+                    problems.push(format!(
+                        "map {index}: wall {} refers to missing room {room}",
+                        wall.id
+                    ));
+                }
+            }
+        }
+
+        // Broken saves can trip assertions in the triangulation, which should be reported like
+        // any other problem.
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            Map::from_model(map, &mut EntityHashMap::<Entity>::default())
+        }));
+        match res {
+            Ok(Ok(_)) => {}
+            Ok(Err(error)) => problems.push(format!("map {index}: {error}")),
+            Err(_) => problems.push(format!("map {index}: panicked while building map")),
+        }
+    }
+
+    let pawns: EntityHashSet = save.pawns.iter().map(|pawn| pawn.id).collect();
+    for item in &save.items {
+        match item.holder {
+            Some(holder) if !pawns.contains(&holder) => {
+                problems.push(format!("item {} is held by missing pawn {holder}", item.id))
+            }
+            None if item.position.is_none() => {
+                problems.push(format!("item {} has no position or holder", item.id))
+            }
+            _ => {}
+        }
+    }
+    for task in &save.tasks {
+        if !pawns.contains(&task.actor) {
+            problems.push(format!(
+                "task {} belongs to missing pawn {}",
+                task.id, task.actor
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Removes every pawn, along with their tasks and groups. Items they were holding are dropped
+/// where they stood. Returns the number of pawns removed.
+fn strip_pawns(save: &mut SaveModel) -> usize {
+    let positions: EntityHashMap<Vec2> = save
+        .pawns
+        .iter()
+        .map(|pawn| (pawn.id, pawn.position))
+        .collect();
+    for item in &mut save.items {
+        if let Some(holder) = item.holder.take() {
+            item.position = positions.get(&holder).copied().or(item.position);
+        }
+    }
+
+    save.tasks.clear();
+    save.groups.clear();
+    save.pawns.drain(..).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use pb_engine::save::SaveInfo;
+
+    use super::*;
+
+    fn full_save() -> LoadedSave {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../pb-engine/tests/data/saves/v2/full.json");
+        load(path.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut save = full_save().save;
+        assert_eq!(validate(&save), Vec::<String>::new());
+
+        let corner = save.maps[0].walls[0].corners[0];
+        save.maps[0].corners.retain(|c| c.id != corner);
+        let problems = validate(&save);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains(&format!("missing corner {corner}")));
+    }
+
+    #[test]
+    fn test_strip_pawns() {
+        let loaded = full_save();
+        assert_eq!(loaded.version, 2);
+        let mut save = loaded.save;
+        let held = save
+            .items
+            .iter()
+            .filter(|item| item.holder.is_some())
+            .count();
+        assert!(held > 0);
+
+        assert_eq!(strip_pawns(&mut save), 6);
+        assert!(save.pawns.is_empty() && save.tasks.is_empty() && save.groups.is_empty());
+        assert!(save.items.iter().all(|item| item.position.is_some()));
+        assert_eq!(validate(&save), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_strip_pawns_archive() {
+        let dir = env::temp_dir().join(format!("pb-save-tool-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("full.pbsave");
+
+        let loaded = full_save();
+        let info = serde_json::to_vec(&loaded.save.info()).unwrap();
+        let archive = loaded
+            .archive
+            .with_sidecar(Sidecar::Info, info)
+            .with_sidecar(Sidecar::Thumbnail, vec![1, 2, 3]);
+        fs::write(&path, archive.to_bytes()).unwrap();
+
+        let path_str = path.to_str().unwrap();
+        assert!(run(&["strip-pawns", path_str]).unwrap());
+
+        let stripped = load(path_str).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(stripped.save.pawns.is_empty());
+        assert_eq!(stripped.archive.format(), Format::Json);
+        let thumbnail = stripped.archive.sidecar(Sidecar::Thumbnail).unwrap();
+        assert_eq!(thumbnail, thumbnail::render(&stripped.save).unwrap());
+        let info: SaveInfo =
+            serde_json::from_slice(stripped.archive.sidecar(Sidecar::Info).unwrap()).unwrap();
+        assert_eq!(info.pawns, 0);
+    }
+}
@@ -0,0 +1,214 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+
+use crate::{Format, Sidecar, decode};
+
+/// The bytes at the start of every archive.
+const MAGIC: [u8; 4] = *b"PBAR";
+const VERSION: u16 = 1;
+
+/// A value and its sidecars bundled into a single file, for sharing values between stores.
+///
+/// The format is the magic number and version, followed by the number of entries and then each
+/// entry as its length-prefixed suffix and contents. The value itself has an empty suffix, and
+/// sidecars use the suffix of their key. Integers are little-endian.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Archive {
+    value: Vec<u8>,
+    sidecars: Vec<(Sidecar, Vec<u8>)>,
+}
+
+impl Archive {
+    pub const EXTENSION: &str = "pbsave";
+
+    pub fn new(value: Vec<u8>) -> Self {
+        Archive {
+            value,
+            sidecars: Vec::new(),
+        }
+    }
+
+    pub fn with_sidecar(mut self, sidecar: Sidecar, bytes: Vec<u8>) -> Self {
+        self.sidecars.retain(|&(s, _)| s != sidecar);
+        self.sidecars.push((sidecar, bytes));
+        self
+    }
+
+    pub fn sidecar(&self, sidecar: Sidecar) -> Option<&[u8]> {
+        self.sidecars
+            .iter()
+            .find(|&&(s, _)| s == sidecar)
+            .map(|(_, bytes)| bytes.as_slice())
+    }
+
+    /// The format of the archived value.
+    pub fn format(&self) -> Format {
+        Format::detect(&self.value)
+    }
+
+    /// Deserializes the archived value.
+    pub fn decode<T>(&self) -> Result<T>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        decode(PhantomData, &self.value)
+    }
+
+    pub(crate) fn into_parts(self) -> (Vec<u8>, Vec<(Sidecar, Vec<u8>)>) {
+        (self.value, self.sidecars)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.sidecars.len() as u16 + 1).to_le_bytes());
+
+        let entries = std::iter::once(("", &self.value)).chain(
+            self.sidecars
+                .iter()
+                .map(|(sidecar, bytes)| (sidecar.suffix(), bytes)),
+        );
+        for (suffix, contents) in entries {
+            bytes.extend_from_slice(&(suffix.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(suffix.as_bytes());
+            bytes.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(contents);
+        }
+        bytes
+    }
+
+    /// Parses an archive, checking it is well-formed and contains a value.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader(bytes);
+
+        if reader.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+            return Err("not a save archive".into());
+        }
+        let version = reader.u16()?;
+        if version != VERSION {
+            return Err(format!("unsupported archive version {version}").into());
+        }
+
+        let mut value = None;
+        let mut archive = Archive::new(Vec::new());
+        for _ in 0..reader.u16()? {
+            let suffix_len = reader.u16()? as usize;
+            let suffix = std::str::from_utf8(reader.take(suffix_len)?)
+                .map_err(|_| "invalid entry name in archive")?;
+            let contents_len = usize::try_from(reader.u64()?)
+                .map_err(|_| format!("entry '{suffix}' in archive is too large"))?;
+            let contents = reader.take(contents_len)?.to_vec();
+
+            if suffix.is_empty() {
+                if value.replace(contents).is_some() {
+                    return Err("archive contains more than one value".into());
+                }
+                continue;
+            }
+
+            let sidecar = Sidecar::ALL
+                .into_iter()
+                .find(|sidecar| sidecar.suffix() == suffix)
+                .ok_or_else(|| format!("unknown entry '{suffix}' in archive"))?;
+            if archive.sidecar(sidecar).is_some() {
+                return Err(format!("archive contains entry '{suffix}' more than once").into());
+            }
+            archive = archive.with_sidecar(sidecar, contents);
+        }
+
+        if !reader.0.is_empty() {
+            return Err("unexpected data at the end of the archive".into());
+        }
+
+        archive.value = value.ok_or("archive does not contain a value")?;
+        Ok(archive)
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err("archive is truncated".into());
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::tasks::block_on;
+
+    use crate::Store;
+
+    use super::*;
+
+    #[test]
+    fn test_export_and_import() {
+        let store = Store::memory();
+
+        block_on(async {
+            store.set("saves/a.sav", vec![1, 2, 3]).await.unwrap();
+            store
+                .set(&Sidecar::Info.key("saves/a.sav"), "info".to_owned())
+                .await
+                .unwrap();
+
+            let archive = store.export("saves/a.sav").await.unwrap();
+            assert_eq!(archive.format(), Format::Binary);
+            assert!(archive.sidecar(Sidecar::Thumbnail).is_none());
+
+            let bytes = archive.to_bytes();
+            let archive = Archive::from_bytes(&bytes).unwrap();
+            assert_eq!(archive.decode::<Vec<u32>>().unwrap(), [1, 2, 3]);
+
+            store.import("saves/b.sav", archive).await.unwrap();
+            assert_eq!(
+                store.get::<Vec<u32>>("saves/b.sav").await.unwrap(),
+                [1, 2, 3]
+            );
+            assert_eq!(
+                store
+                    .get::<String>(&Sidecar::Info.key("saves/b.sav"))
+                    .await
+                    .unwrap(),
+                "info"
+            );
+        });
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = |bytes: &[u8]| Archive::from_bytes(bytes).unwrap_err().to_string();
+
+        let bytes = Archive::new(vec![1, 2, 3]).to_bytes();
+        assert!(error(b"{\"version\": 2}").contains("not a save archive"));
+        assert!(error(&bytes[..bytes.len() - 1]).contains("truncated"));
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(error(&trailing).contains("unexpected data"));
+
+        let mut future_version = bytes.clone();
+        future_version[4] = 2;
+        assert!(error(&future_version).contains("unsupported archive version 2"));
+
+        let mut empty = bytes[..6].to_vec();
+        empty.extend_from_slice(&0u16.to_le_bytes());
+        assert!(error(&empty).contains("does not contain a value"));
+    }
+}
@@ -1,26 +1,90 @@
-#[cfg_attr(not(target_arch = "wasm32"), path = "native.rs")]
-#[cfg_attr(target_arch = "wasm32", path = "web.rs")]
-mod sys;
+mod archive;
+mod memory;
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
-use std::{marker::PhantomData, sync::Arc};
+pub use archive::Archive;
+pub use memory::MemoryBackend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::{DirBackend, export_file, import_file};
+#[cfg(target_arch = "wasm32")]
+pub use web::{LocalStorageBackend, export_file, import_file};
 
-use bevy::prelude::*;
+use std::{io::Read, marker::PhantomData, sync::Arc};
+
+use bevy::{prelude::*, tasks::BoxedFuture};
 use chrono::{DateTime, Local, Utc};
+use ruzstd::{
+    decoding::StreamingDecoder,
+    encoding::{CompressionLevel, compress_to_vec},
+};
 use serde::{
     Deserialize, Serialize,
     de::{DeserializeOwned, DeserializeSeed},
 };
 use smol_str::SmolStr;
 
+/// The magic number at the start of a zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// The extension appended to keys while their values are being written.
+const TEMP_EXTENSION: &str = "tmp";
+/// The directory beside a key which holds its numbered backups.
+const BACKUP_DIR: &str = ".backups";
+
 pub struct PbStorePlugin;
 
 #[derive(Clone, Resource)]
-pub struct Store(Arc<sys::Store>);
+pub struct Store(Arc<dyn Backend>);
+
+/// The storage underlying a [`Store`], which holds encoded values by key.
+///
+/// Keys are paths separated by `/`, where the last segment is a file name with an extension.
+pub trait Backend: Send + Sync + 'static {
+    /// Reads the value at a key, returning `None` if there is no value stored.
+    fn read<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Option<Vec<u8>>>>;
+
+    /// Writes the value at a key, replacing any existing value.
+    fn write<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> BoxedFuture<'a, Result>;
+
+    /// Deletes the value at a key. Returns `false` if there was no value to delete.
+    fn delete<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<bool>>;
+
+    /// Moves the value at one key to another, replacing any existing value. Returns `false` if
+    /// there was no value to move.
+    fn rename<'a>(&'a self, from: &'a str, to: &'a str) -> BoxedFuture<'a, Result<bool>>;
+
+    /// Lists the values directly under a key.
+    fn list<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Vec<Metadata>>>;
+}
+
+/// A value stored alongside another under a key with an added suffix. Sidecars are not listed by
+/// [`Store::iter`], and are moved, copied and deleted along with the value they belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sidecar {
+    /// A summary of the value, such as the details of a save shown in the saves menu.
+    Info,
+    /// A PNG preview image of the value.
+    Thumbnail,
+}
+
+/// The encoding used to store a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable JSON, used for settings and for exporting saves.
+    Json,
+    /// MessagePack compressed with zstd, used for saves.
+    Binary,
+}
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Metadata {
     pub name: SmolStr,
     pub modified: DateTime<Utc>,
+    /// The full key of the value, including its extension.
+    #[serde(skip)]
+    pub key: SmolStr,
 }
 
 impl Plugin for PbStorePlugin {
@@ -30,10 +94,30 @@ impl Plugin for PbStorePlugin {
 }
 
 impl Store {
+    /// Creates a store in the default location for the platform: the user's data directory on
+    /// native platforms, or local storage on the web.
     pub fn new() -> Self {
-        Store(Arc::new(
-            sys::Store::new().expect("failed to initialize storage"),
-        ))
+        #[cfg(not(target_arch = "wasm32"))]
+        let backend = DirBackend::user_data().expect("failed to initialize storage");
+        #[cfg(target_arch = "wasm32")]
+        let backend = LocalStorageBackend::new().expect("failed to initialize storage");
+
+        Store::with_backend(backend)
+    }
+
+    pub fn with_backend(backend: impl Backend) -> Self {
+        Store(Arc::new(backend))
+    }
+
+    /// Creates a store in a custom directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn dir(path: impl Into<std::path::PathBuf>) -> Self {
+        Store::with_backend(DirBackend::new(path))
+    }
+
+    /// Creates a store which only keeps values in memory, for tests and headless tools.
+    pub fn memory() -> Self {
+        Store::with_backend(MemoryBackend::default())
     }
 
     pub async fn get<T>(&self, key: &str) -> Result<T>
@@ -55,8 +139,7 @@ impl Store {
         S: for<'de> DeserializeSeed<'de, Value = T>,
         T: TypePath + Send + 'static,
     {
-        self.0
-            .get(key, seed)
+        self.try_get_with(key, seed)
             .await?
             .ok_or_else(|| format!("file '{key}' not found").into())
     }
@@ -66,18 +149,208 @@ impl Store {
         S: for<'de> DeserializeSeed<'de, Value = T>,
         T: TypePath + Send + 'static,
     {
-        self.0.get(key, seed).await
+        let Some(bytes) = self.0.read(key).await? else {
+            return Ok(None);
+        };
+
+        let value =
+            decode(seed, &bytes).map_err(|error| format!("failed to load '{key}': {error}"))?;
+        info!(
+            "Loaded value of type '{}' from '{key}'",
+            T::short_type_path()
+        );
+        Ok(Some(value))
+    }
+
+    /// Loads the raw bytes at a key, for values which are not serialized such as images.
+    pub async fn try_get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        self.0.read(key).await
+    }
+
+    /// Loads a value, falling back to the most recent of its numbered backups if the value is
+    /// missing or fails to parse.
+    pub async fn get_with_backups<T>(&self, key: &str, backups: usize) -> Result<T>
+    where
+        T: TypePath + DeserializeOwned + Send,
+    {
+        let error = match self.get(key).await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        for n in 1..=backups {
+            let backup = backup_key(key, n);
+            match self.try_get(&backup).await {
+                Ok(Some(value)) => {
+                    warn!("Loaded backup '{backup}' instead: {error}");
+                    return Ok(value);
+                }
+                Ok(None) => (),
+                Err(error) => warn!("Failed to load backup: {error}"),
+            }
+        }
+
+        Err(error)
     }
 
+    /// Stores a value, in the binary format if the key has the
+    /// [`BINARY_EXTENSION`](Format::BINARY_EXTENSION) or as JSON otherwise.
     pub async fn set<T>(&self, key: &str, value: T) -> Result<()>
     where
         T: Serialize + TypePath + Send,
     {
-        self.0.set(key, value).await
+        self.set_with_backups(key, value, 0).await
+    }
+
+    /// Stores a value, first moving the previous value to a numbered backup and keeping at most
+    /// `backups` of them.
+    pub async fn set_with_backups<T>(&self, key: &str, value: T, backups: usize) -> Result<()>
+    where
+        T: Serialize + TypePath + Send,
+    {
+        let bytes = encode(&value, Format::from_key(key))?;
+
+        if backups > 0 {
+            // Write the new value before rotating the backups, so the previous value is left in
+            // place if the write fails.
+            let temp = temp_key(key);
+            self.0.write(&temp, bytes).await?;
+
+            for n in (1..backups).rev() {
+                self.rename(&backup_key(key, n), &backup_key(key, n + 1))
+                    .await?;
+            }
+            self.rename(key, &backup_key(key, 1)).await?;
+            self.0.rename(&temp, key).await?;
+        } else {
+            self.0.write(key, bytes).await?;
+        }
+        info!("Stored value of type '{}' at '{key}'", T::short_type_path());
+        Ok(())
+    }
+
+    /// Stores raw bytes at a key, for values which are not serialized such as images.
+    pub async fn set_bytes(&self, key: &str, bytes: Vec<u8>) -> Result<()> {
+        let len = bytes.len();
+        self.0.write(key, bytes).await?;
+        info!("Stored {len} bytes at '{key}'");
+        Ok(())
+    }
+
+    /// Bundles a value and its sidecars into an archive.
+    pub async fn export(&self, key: &str) -> Result<Archive> {
+        let value = self
+            .0
+            .read(key)
+            .await?
+            .ok_or_else(|| format!("file '{key}' not found"))?;
+
+        let mut archive = Archive::new(value);
+        for sidecar in Sidecar::ALL {
+            if let Some(bytes) = self.0.read(&sidecar.key(key)).await? {
+                archive = archive.with_sidecar(sidecar, bytes);
+            }
+        }
+        Ok(archive)
+    }
+
+    /// Stores the value and sidecars from an archive at a key, replacing any existing value and
+    /// sidecars.
+    pub async fn import(&self, key: &str, archive: Archive) -> Result<()> {
+        let (value, mut sidecars) = archive.into_parts();
+        self.0.write(key, value).await?;
+
+        for sidecar in Sidecar::ALL {
+            match sidecars.iter().position(|&(s, _)| s == sidecar) {
+                Some(index) => {
+                    let (_, bytes) = sidecars.swap_remove(index);
+                    self.0.write(&sidecar.key(key), bytes).await?;
+                }
+                None => {
+                    self.0.delete(&sidecar.key(key)).await?;
+                }
+            }
+        }
+
+        info!("Imported archive to '{key}'");
+        Ok(())
+    }
+
+    /// Deletes a value and its sidecars. Returns `false` if there was no value at `key`.
+    pub async fn delete(&self, key: &str) -> Result<bool> {
+        let deleted = self.0.delete(key).await?;
+        for sidecar in Sidecar::ALL {
+            self.0.delete(&sidecar.key(key)).await?;
+        }
+
+        if deleted {
+            info!("Deleted value at '{key}'");
+        }
+        Ok(deleted)
+    }
+
+    /// Moves a value and its sidecars to a different key. Returns `false` if there was no value at
+    /// `from`.
+    pub async fn rename(&self, from: &str, to: &str) -> Result<bool> {
+        let renamed = self.0.rename(from, to).await?;
+        for sidecar in Sidecar::ALL {
+            self.0.rename(&sidecar.key(from), &sidecar.key(to)).await?;
+        }
+
+        if renamed {
+            info!("Moved value at '{from}' to '{to}'");
+        }
+        Ok(renamed)
     }
 
+    /// Copies a value and its sidecars to a different key. Returns `false` if there was no value at
+    /// `from`.
+    pub async fn duplicate(&self, from: &str, to: &str) -> Result<bool> {
+        let Some(bytes) = self.0.read(from).await? else {
+            return Ok(false);
+        };
+        self.0.write(to, bytes).await?;
+
+        for sidecar in Sidecar::ALL {
+            if let Some(bytes) = self.0.read(&sidecar.key(from)).await? {
+                self.0.write(&sidecar.key(to), bytes).await?;
+            }
+        }
+
+        info!("Copied value at '{from}' to '{to}'");
+        Ok(true)
+    }
+
+    /// Lists the values directly under a key, excluding sidecars and backups.
     pub async fn iter(&self, key: &str) -> Result<Vec<Metadata>> {
-        self.0.iter(key).await
+        let mut results = self.0.list(key).await?;
+        results.retain(|metadata| {
+            !is_temp_key(&metadata.key)
+                && !is_backup_key(&metadata.key)
+                && Sidecar::ALL.iter().all(|s| !s.is_key(&metadata.key))
+        });
+        Ok(results)
+    }
+
+    /// Lists the values directly under a key, along with their [`Info`](Sidecar::Info) sidecars.
+    ///
+    /// Values whose sidecar is missing or fails to load are returned with `None`.
+    pub async fn iter_with_info<T>(&self, key: &str) -> Result<Vec<(Metadata, Option<T>)>>
+    where
+        T: TypePath + DeserializeOwned + Send,
+    {
+        let mut results = Vec::new();
+        for metadata in self.iter(key).await? {
+            let info = match self.try_get(&Sidecar::Info.key(&metadata.key)).await {
+                Ok(info) => info,
+                Err(error) => {
+                    warn!("Failed to load info: {error}");
+                    None
+                }
+            };
+            results.push((metadata, info));
+        }
+        Ok(results)
     }
 }
 
@@ -92,6 +365,7 @@ impl Metadata {
         Metadata {
             name: name.into(),
             modified: Utc::now(),
+            key: SmolStr::default(),
         }
     }
 
@@ -100,12 +374,140 @@ impl Metadata {
     }
 }
 
-fn from_json<S, T>(seed: S, json: &str) -> Result<T, serde_json::Error>
+impl Sidecar {
+    pub const ALL: [Sidecar; 2] = [Sidecar::Info, Sidecar::Thumbnail];
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Sidecar::Info => ".info.json",
+            Sidecar::Thumbnail => ".png",
+        }
+    }
+
+    /// The key of this sidecar for the value at `key`.
+    pub fn key(self, key: &str) -> String {
+        format!("{key}{}", self.suffix())
+    }
+
+    fn is_key(self, key: &str) -> bool {
+        key.ends_with(self.suffix())
+    }
+}
+
+impl Format {
+    pub const BINARY_EXTENSION: &str = "sav";
+    pub const JSON_EXTENSION: &str = "json";
+
+    /// Chooses the format for a key from its extension.
+    pub fn from_key(key: &str) -> Self {
+        match key.rsplit_once('.') {
+            Some((_, Self::BINARY_EXTENSION)) => Format::Binary,
+            _ => Format::Json,
+        }
+    }
+
+    /// Serializes a value in this format.
+    pub fn encode<T>(self, value: &T) -> Result<Vec<u8>>
+    where
+        T: Serialize,
+    {
+        encode(value, self)
+    }
+
+    /// Detects the format of an encoded value from its contents.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&ZSTD_MAGIC) {
+            Format::Binary
+        } else {
+            Format::Json
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Json => Self::JSON_EXTENSION,
+            Format::Binary => Self::BINARY_EXTENSION,
+        }
+    }
+}
+
+fn encode<T>(value: &T, format: Format) -> Result<Vec<u8>>
+where
+    T: Serialize,
+{
+    match format {
+        Format::Json => Ok(serde_json::to_vec(value)
+            .map_err(|error| format!("failed to serialize JSON: {error}"))?),
+        Format::Binary => {
+            let bytes = rmp_serde::to_vec_named(value)
+                .map_err(|error| format!("failed to serialize MessagePack: {error}"))?;
+            Ok(compress_to_vec(bytes.as_slice(), CompressionLevel::Fastest))
+        }
+    }
+}
+
+/// Deserializes a value in either format, detecting binary values by the zstd frame header.
+fn decode<S, T>(seed: S, bytes: &[u8]) -> Result<T>
+where
+    S: for<'de> DeserializeSeed<'de, Value = T>,
+    T: 'static,
+{
+    if Format::detect(bytes) == Format::Binary {
+        let mut decoder = StreamingDecoder::new(bytes)
+            .map_err(|error| format!("failed to decompress: {error}"))?;
+        let mut decompressed = Vec::new();
+        decoder
+            .read_to_end(&mut decompressed)
+            .map_err(|error| format!("failed to decompress: {error}"))?;
+
+        let mut de = rmp_serde::Deserializer::new(decompressed.as_slice());
+        Ok(seed
+            .deserialize(&mut de)
+            .map_err(|error| format!("failed to parse MessagePack: {error}"))?)
+    } else {
+        Ok(from_json(seed, bytes).map_err(|error| format!("failed to parse JSON: {error}"))?)
+    }
+}
+
+/// Returns the key of a numbered backup, which is kept in a directory beside the original key so
+/// it can't collide with other values. It keeps the extension of the original key so it is stored
+/// in the same format, e.g. `saves/.backups/autosave.1.sav`.
+pub fn backup_key(key: &str, n: usize) -> String {
+    let (dir, name) = match key.rsplit_once('/') {
+        Some((dir, name)) => (format!("{dir}/{BACKUP_DIR}"), name),
+        None => (BACKUP_DIR.to_owned(), key),
+    };
+    match name.rsplit_once('.') {
+        Some((stem, extension)) => format!("{dir}/{stem}.{n}.{extension}"),
+        None => format!("{dir}/{name}.{n}"),
+    }
+}
+
+fn is_backup_key(key: &str) -> bool {
+    key.split('/').any(|segment| segment == BACKUP_DIR)
+}
+
+fn temp_key(key: &str) -> String {
+    format!("{key}.{TEMP_EXTENSION}")
+}
+
+fn is_temp_key(key: &str) -> bool {
+    key.rsplit_once('.')
+        .is_some_and(|(_, extension)| extension == TEMP_EXTENSION)
+}
+
+/// Returns the name of a key without its directory or extension.
+fn file_stem(key: &str) -> &str {
+    let name = key.rsplit_once('/').map(|(_, s)| s).unwrap_or(key);
+    name.rsplit_once('.').map(|(s, _)| s).unwrap_or(name)
+}
+
+fn from_json<S, T>(seed: S, json: &[u8]) -> Result<T, serde_json::Error>
 where
     S: for<'de> DeserializeSeed<'de, Value = T>,
     T: 'static,
 {
-    let mut de = serde_json::Deserializer::from_str(json);
+    let mut de = serde_json::Deserializer::from_slice(json);
     let value = seed.deserialize(&mut de)?;
     de.end()?;
     Ok(value)
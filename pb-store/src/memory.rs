@@ -0,0 +1,214 @@
+use std::{
+    collections::BTreeMap,
+    future,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use bevy::{prelude::*, tasks::BoxedFuture};
+use chrono::{DateTime, Utc};
+use smol_str::SmolStr;
+
+use crate::{Backend, Metadata, file_stem};
+
+/// A backend which keeps values in memory, and loses them when dropped.
+#[derive(Default)]
+pub struct MemoryBackend {
+    values: Mutex<Values>,
+}
+
+/// Each stored value, along with when it was last modified.
+type Values = BTreeMap<SmolStr, (Vec<u8>, DateTime<Utc>)>;
+
+impl MemoryBackend {
+    fn values(&self) -> MutexGuard<'_, Values> {
+        self.values.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn read<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Option<Vec<u8>>>> {
+        let bytes = self.values().get(key).map(|(bytes, _)| bytes.clone());
+        Box::pin(future::ready(Ok(bytes)))
+    }
+
+    fn write<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> BoxedFuture<'a, Result> {
+        self.values().insert(key.into(), (bytes, Utc::now()));
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<bool>> {
+        let deleted = self.values().remove(key).is_some();
+        Box::pin(future::ready(Ok(deleted)))
+    }
+
+    fn rename<'a>(&'a self, from: &'a str, to: &'a str) -> BoxedFuture<'a, Result<bool>> {
+        let mut values = self.values();
+        let renamed = match values.remove(from) {
+            Some(value) => {
+                values.insert(to.into(), value);
+                true
+            }
+            None => false,
+        };
+        Box::pin(future::ready(Ok(renamed)))
+    }
+
+    fn list<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Vec<Metadata>>> {
+        let prefix = format!("{key}/");
+        let results = self
+            .values()
+            .iter()
+            .filter(|(key, _)| {
+                key.strip_prefix(&prefix)
+                    .is_some_and(|name| !name.contains('/'))
+            })
+            .map(|(key, &(_, modified))| Metadata {
+                name: file_stem(key).into(),
+                modified,
+                key: key.clone(),
+            })
+            .collect();
+        Box::pin(future::ready(Ok(results)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::tasks::block_on;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{Sidecar, Store};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypePath)]
+    struct TestValue {
+        name: String,
+        count: u32,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let store = Store::memory();
+        let value = TestValue {
+            name: "test".to_owned(),
+            count: 3,
+        };
+
+        block_on(async {
+            store.set("saves/a.sav", value.clone()).await.unwrap();
+            store.set("saves/b.json", value.clone()).await.unwrap();
+            store
+                .set("saves/nested/c.json", value.clone())
+                .await
+                .unwrap();
+
+            assert_eq!(store.get::<TestValue>("saves/a.sav").await.unwrap(), value);
+            assert_eq!(store.get::<TestValue>("saves/b.json").await.unwrap(), value);
+            assert!(
+                store
+                    .try_get::<TestValue>("saves/d.json")
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+
+            let saves = store.iter("saves").await.unwrap();
+            let names: Vec<_> = saves.iter().map(|metadata| &metadata.name).collect();
+            let keys: Vec<_> = saves.iter().map(|metadata| &metadata.key).collect();
+            assert_eq!(names, ["a", "b"]);
+            assert_eq!(keys, ["saves/a.sav", "saves/b.json"]);
+        });
+    }
+
+    #[test]
+    fn test_backups() {
+        let store = Store::memory();
+
+        block_on(async {
+            for count in 0..4 {
+                let value = TestValue {
+                    name: "test".to_owned(),
+                    count,
+                };
+                store
+                    .set_with_backups("saves/a.sav", value, 2)
+                    .await
+                    .unwrap();
+            }
+
+            let counts = [
+                store.get::<TestValue>("saves/a.sav").await.unwrap().count,
+                store
+                    .get::<TestValue>("saves/.backups/a.1.sav")
+                    .await
+                    .unwrap()
+                    .count,
+                store
+                    .get::<TestValue>("saves/.backups/a.2.sav")
+                    .await
+                    .unwrap()
+                    .count,
+            ];
+            assert_eq!(counts, [3, 2, 1]);
+            assert!(
+                store
+                    .try_get::<TestValue>("saves/.backups/a.3.sav")
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+
+            store
+                .set("saves/a.sav", "corrupt".to_owned())
+                .await
+                .unwrap();
+            let value = store
+                .get_with_backups::<TestValue>("saves/a.sav", 2)
+                .await
+                .unwrap();
+            assert_eq!(value.count, 2);
+
+            let saves = store.iter("saves").await.unwrap();
+            let names: Vec<_> = saves.iter().map(|metadata| &metadata.name).collect();
+            assert_eq!(names, ["a"]);
+        });
+    }
+
+    #[test]
+    fn test_sidecars() {
+        let store = Store::memory();
+        let value = TestValue {
+            name: "test".to_owned(),
+            count: 3,
+        };
+
+        block_on(async {
+            store.set("saves/a.sav", value.clone()).await.unwrap();
+            store
+                .set(&Sidecar::Info.key("saves/a.sav"), value.clone())
+                .await
+                .unwrap();
+
+            assert!(store.duplicate("saves/a.sav", "saves/b.sav").await.unwrap());
+            assert!(store.rename("saves/a.sav", "saves/c.sav").await.unwrap());
+            assert!(!store.rename("saves/a.sav", "saves/d.sav").await.unwrap());
+
+            let saves = store.iter_with_info::<TestValue>("saves").await.unwrap();
+            let names: Vec<_> = saves.iter().map(|(metadata, _)| &metadata.name).collect();
+            assert_eq!(names, ["b", "c"]);
+            assert!(saves.iter().all(|(_, info)| info.as_ref() == Some(&value)));
+
+            assert!(store.delete("saves/b.sav").await.unwrap());
+            assert!(!store.delete("saves/b.sav").await.unwrap());
+            assert!(
+                store
+                    .try_get::<TestValue>(&Sidecar::Info.key("saves/b.sav"))
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+            assert_eq!(store.iter("saves").await.unwrap().len(), 1);
+        });
+    }
+}
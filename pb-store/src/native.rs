@@ -1,122 +1,250 @@
-use std::{ffi::OsStr, io, path::PathBuf};
-
-use bevy::{prelude::*, tasks::futures_lite::StreamExt};
-use directories::ProjectDirs;
-use serde::{Serialize, de::DeserializeSeed};
-
-use crate::{Metadata, from_json};
-
-pub(crate) struct Store {
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*,
+    tasks::{
+        BoxedFuture,
+        futures_lite::{AsyncWriteExt, StreamExt},
+    },
+};
+use directories::{ProjectDirs, UserDirs};
+
+use crate::{Backend, Metadata, TEMP_EXTENSION, file_stem};
+
+/// A backend which stores each value as a file in a directory.
+pub struct DirBackend {
     path: PathBuf,
 }
 
-impl Store {
-    pub fn new() -> Result<Self> {
+impl DirBackend {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        DirBackend { path: path.into() }
+    }
+
+    /// Creates a backend in the game's directory within the user's data directory.
+    pub fn user_data() -> Result<Self> {
         let dirs = ProjectDirs::from("pb.dev.andrewhickman", "", "open-prison-builder")
             .ok_or("failed to find data directory")?;
 
-        Ok(Store {
-            path: dirs.data_dir().to_owned(),
-        })
+        Ok(DirBackend::new(dirs.data_dir()))
     }
 }
 
-impl Store {
-    pub async fn get<S, T>(&self, key: &str, seed: S) -> Result<Option<T>>
-    where
-        S: for<'de> DeserializeSeed<'de, Value = T>,
-        T: TypePath + 'static,
-    {
-        let path = self.path.join(key);
-        let json = match async_fs::read_to_string(&path).await {
-            Ok(json) => json,
-            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
-            Err(error) => {
-                return Err(format!("failed to read from '{}': {error}", path.display()).into());
+impl Backend for DirBackend {
+    fn read<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Option<Vec<u8>>>> {
+        Box::pin(async move {
+            let path = self.path.join(key);
+            match async_fs::read(&path).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(error) => {
+                    Err(format!("failed to read from '{}': {error}", path.display()).into())
+                }
             }
-        };
+        })
+    }
+
+    fn write<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> BoxedFuture<'a, Result> {
+        Box::pin(async move {
+            let path = self.path.join(key);
+            if let Some(dir) = path.parent() {
+                async_fs::create_dir_all(&dir).await.map_err(|error| {
+                    format!("failed to create directory '{}': {error}", dir.display())
+                })?;
+            }
+
+            // Write to a temporary file first, so a crash mid-write can't corrupt the existing
+            // value.
+            let temp_path = temp_path(&path);
+            let write = async {
+                let mut file = async_fs::File::create(&temp_path).await?;
+                file.write_all(&bytes).await?;
+                file.sync_all().await
+            };
+            write.await.map_err(|error| {
+                format!("failed to write to '{}': {error}", temp_path.display())
+            })?;
+
+            async_fs::rename(&temp_path, &path).await.map_err(|error| {
+                format!(
+                    "failed to rename '{}' to '{}': {error}",
+                    temp_path.display(),
+                    path.display()
+                )
+            })?;
 
-        let value = from_json(seed, &json)
-            .map_err(|error| format!("failed to parse JSON at '{}': {error}", path.display()))?;
-        info!(
-            "Loaded value of type '{}' from '{}'",
-            T::short_type_path(),
-            path.display()
-        );
-        Ok(Some(value))
+            Ok(())
+        })
     }
 
-    pub async fn set<T>(&self, key: &str, value: T) -> Result<()>
-    where
-        T: Serialize + TypePath,
-    {
-        let path = self.path.join(key);
-        let json = serde_json::to_string(&value)?;
-
-        match async_fs::write(&path, &json).await {
-            Ok(()) => (),
-            Err(error) if error.kind() == io::ErrorKind::NotFound => {
-                let Some(dir) = path.parent() else {
-                    return Err(format!("failed to write to '{}': {error}", path.display()).into());
-                };
+    fn delete<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            let path = self.path.join(key);
+            match async_fs::remove_file(&path).await {
+                Ok(()) => Ok(true),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+                Err(error) => Err(format!("failed to delete '{}': {error}", path.display()).into()),
+            }
+        })
+    }
 
+    fn rename<'a>(&'a self, from: &'a str, to: &'a str) -> BoxedFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            let from = self.path.join(from);
+            let to = self.path.join(to);
+            if let Some(dir) = to.parent() {
                 async_fs::create_dir_all(&dir).await.map_err(|error| {
                     format!("failed to create directory '{}': {error}", dir.display())
                 })?;
-                async_fs::write(&path, &json)
-                    .await
-                    .map_err(|error| format!("failed to write to '{}': {error}", path.display()))?;
             }
-            Err(error) => {
-                return Err(format!("failed to write to '{}': {error}", path.display()).into());
+
+            match async_fs::rename(&from, &to).await {
+                Ok(()) => Ok(true),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+                Err(error) => Err(format!(
+                    "failed to rename '{}' to '{}': {error}",
+                    from.display(),
+                    to.display()
+                )
+                .into()),
             }
-        };
+        })
+    }
+
+    fn list<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Vec<Metadata>>> {
+        Box::pin(async move {
+            let path = self.path.join(key);
+
+            let mut files = match async_fs::read_dir(&path).await {
+                Ok(files) => files.map(|res| {
+                    res.map_err(|error| {
+                        format!("failed to read directory '{}': {error}", path.display())
+                    })
+                }),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+                Err(error) => {
+                    return Err(
+                        format!("failed to read directory '{}': {error}", path.display()).into(),
+                    );
+                }
+            };
+
+            let mut results = Vec::new();
+            while let Some(entry) = files.try_next().await? {
+                let entry_path = entry.path();
+                if entry_path.extension() == Some(OsStr::new(TEMP_EXTENSION)) {
+                    continue;
+                }
 
-        info!(
-            "Stored value of type '{}' at '{}'",
-            T::short_type_path(),
-            path.display()
-        );
-        Ok(())
+                let Some(name) = entry_path.file_stem().and_then(OsStr::to_str) else {
+                    continue;
+                };
+
+                let metadata = entry.metadata().await.map_err(|error| {
+                    format!(
+                        "failed to get metadata for '{}': {error}",
+                        entry_path.display()
+                    )
+                })?;
+
+                let Some(file_name) = entry_path.file_name().and_then(OsStr::to_str) else {
+                    continue;
+                };
+
+                results.push(Metadata {
+                    name: name.into(),
+                    modified: metadata.modified()?.into(),
+                    key: format!("{key}/{file_name}").into(),
+                });
+            }
+
+            Ok(results)
+        })
     }
+}
 
-    pub async fn iter(&self, key: &str) -> Result<Vec<Metadata>> {
-        let path = self.path.join(key);
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".");
+    temp_path.push(TEMP_EXTENSION);
+    PathBuf::from(temp_path)
+}
 
-        let mut files = match async_fs::read_dir(&path).await {
-            Ok(files) => files.map(|res| {
-                res.map_err(|error| {
-                    format!("failed to read directory '{}': {error}", path.display())
-                })
-            }),
-            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+/// Gives a file to the user by writing it to a path they entered, replacing any existing file. If
+/// the path is a directory, or empty for their downloads directory, the file is written there as
+/// `file_name` without replacing any existing file. Returns the path it was written to.
+pub async fn export_file(path: &str, file_name: &str, bytes: Vec<u8>) -> Result<String> {
+    let path = Path::new(path.trim());
+    let dir = if path.as_os_str().is_empty() {
+        let dirs = UserDirs::new().ok_or("failed to find user directories")?;
+        dirs.download_dir()
+            .ok_or("failed to find downloads directory")?
+            .to_owned()
+    } else if async_fs::metadata(path)
+        .await
+        .is_ok_and(|metadata| metadata.is_dir())
+    {
+        path.to_owned()
+    } else {
+        async_fs::write(path, &bytes)
+            .await
+            .map_err(|error| format!("failed to write to '{}': {error}", path.display()))?;
+        return Ok(path.display().to_string());
+    };
+    async_fs::create_dir_all(&dir)
+        .await
+        .map_err(|error| format!("failed to create directory '{}': {error}", dir.display()))?;
+
+    let stem = file_stem(file_name);
+    let extension = Path::new(file_name).extension().and_then(OsStr::to_str);
+    for n in 1.. {
+        let name = match (n, extension) {
+            (1, _) => file_name.to_owned(),
+            (n, Some(extension)) => format!("{stem} ({n}).{extension}"),
+            (n, None) => format!("{stem} ({n})"),
+        };
+        let path = dir.join(name);
+
+        let mut file = match async_fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await
+        {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
             Err(error) => {
-                return Err(
-                    format!("failed to read directory '{}': {error}", path.display()).into(),
-                );
+                return Err(format!("failed to create '{}': {error}", path.display()).into());
             }
         };
 
-        let mut results = Vec::new();
-        while let Some(entry) = files.try_next().await? {
-            let entry_path = entry.path();
-            let Some(name) = entry_path.file_stem().and_then(OsStr::to_str) else {
-                continue;
-            };
+        let write = async {
+            file.write_all(&bytes).await?;
+            file.sync_all().await
+        };
+        write
+            .await
+            .map_err(|error| format!("failed to write to '{}': {error}", path.display()))?;
 
-            let metadata = entry.metadata().await.map_err(|error| {
-                format!(
-                    "failed to get metadata for '{}': {error}",
-                    entry_path.display()
-                )
-            })?;
+        return Ok(path.display().to_string());
+    }
 
-            results.push(Metadata {
-                name: name.into(),
-                modified: metadata.modified()?.into(),
-            });
-        }
+    unreachable!()
+}
 
-        Ok(results)
-    }
+/// Reads a file chosen by the user, from a path they entered. Returns the file name and contents.
+pub async fn import_file(path: &str) -> Result<Option<(String, Vec<u8>)>> {
+    let path = Path::new(path.trim());
+    let Some(name) = path.file_name().and_then(OsStr::to_str) else {
+        return Err("no file chosen".into());
+    };
+
+    let bytes = async_fs::read(path)
+        .await
+        .map_err(|error| format!("failed to read '{}': {error}", path.display()))?;
+    Ok(Some((name.to_owned(), bytes)))
 }
@@ -1,103 +1,152 @@
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
 use bevy::{
     ecs::error::{BevyError, Result},
-    log::info,
-    reflect::TypePath,
+    tasks::BoxedFuture,
 };
-use serde::{Serialize, de::DeserializeSeed};
-use wasm_bindgen::{JsCast, JsValue};
+use js_sys::{Array, Promise, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, HtmlAnchorElement, HtmlInputElement, Url};
 
-use crate::{Metadata, from_json};
+use crate::{Backend, Metadata, file_stem};
 
 const META_SUFFIX: &str = ".meta";
+/// How long to keep the URL of an exported file alive, since browsers may start the download after
+/// the link is clicked.
+const EXPORT_URL_LIFETIME_MS: i32 = 10_000;
 
-pub(crate) struct Store;
+/// A backend which stores values in the browser's local storage.
+pub struct LocalStorageBackend;
 
-impl Store {
+impl LocalStorageBackend {
     pub fn new() -> Result<Self> {
-        Ok(Store)
+        Ok(LocalStorageBackend)
     }
 }
 
-impl Store {
-    pub async fn get<S, T>(&self, key: &str, seed: S) -> Result<Option<T>>
-    where
-        S: for<'de> DeserializeSeed<'de, Value = T>,
-        T: TypePath + 'static,
-    {
-        let storage = self.storage()?;
-
-        let json = storage.get_item(key).map_err(map_err)?;
-        if let Some(json) = json {
-            let settings = from_json(seed, &json)
-                .map_err(|err| format!("failed to parse JSON at '{key}': {err}"))?;
-            info!(
-                "Loaded value of type '{}' from '{}'",
-                T::short_type_path(),
-                key,
-            );
-            Ok(Some(settings))
-        } else {
-            Ok(None)
-        }
-    }
+impl Backend for LocalStorageBackend {
+    fn read<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Option<Vec<u8>>>> {
+        Box::pin(async move {
+            let storage = self.storage()?;
 
-    pub async fn set<T>(&self, key: &str, value: T) -> Result
-    where
-        T: Serialize + TypePath,
-    {
-        let storage = self.storage()?;
-
-        let json = serde_json::to_string(&value)
-            .map_err(|err| BevyError::from(format!("failed to serialize JSON: {err}")))?;
-        storage.set_item(key, &json).map_err(map_err)?;
-
-        let metadata = Metadata::new(file_stem(key));
-        let metadata_json = serde_json::to_string(&metadata)
-            .map_err(|err| BevyError::from(format!("failed to serialize JSON: {err}")))?;
-        let metadata_key = format!("{}{}", key, META_SUFFIX);
-        storage
-            .set_item(&metadata_key, &metadata_json)
-            .map_err(map_err)?;
-
-        info!(
-            "Stored value of type '{}' at '{}'",
-            T::short_type_path(),
-            key,
-        );
-        Ok(())
+            let Some(item) = storage.get_item(key).map_err(map_err)? else {
+                return Ok(None);
+            };
+            let bytes = if is_text(key) {
+                item.into_bytes()
+            } else {
+                BASE64
+                    .decode(&item)
+                    .map_err(|err| format!("failed to decode '{key}': {err}"))?
+            };
+            Ok(Some(bytes))
+        })
     }
 
-    pub async fn iter(&self, key: &str) -> Result<Vec<Metadata>> {
-        let storage = self.storage()?;
-        let prefix = if key.is_empty() {
-            String::new()
-        } else {
-            format!("{key}/")
-        };
-        let length = storage.length().map_err(map_err)?;
-
-        let mut results = Vec::new();
-        for i in 0..length {
-            let Some(key) = storage.key(i).map_err(map_err)? else {
-                continue;
+    fn write<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> BoxedFuture<'a, Result> {
+        Box::pin(async move {
+            let storage = self.storage()?;
+
+            // Local storage only holds strings, so binary values are stored as base64.
+            let item = if is_text(key) {
+                String::from_utf8(bytes)?
+            } else {
+                BASE64.encode(bytes)
             };
-            if !key.starts_with(&prefix) || !key.ends_with(META_SUFFIX) {
-                continue;
+            storage.set_item(key, &item).map_err(map_err)?;
+
+            let metadata = Metadata::new(file_stem(key));
+            let metadata_json = serde_json::to_string(&metadata)
+                .map_err(|err| BevyError::from(format!("failed to serialize JSON: {err}")))?;
+            let metadata_key = format!("{}{}", key, META_SUFFIX);
+            storage
+                .set_item(&metadata_key, &metadata_json)
+                .map_err(map_err)?;
+
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            let storage = self.storage()?;
+
+            if storage.get_item(key).map_err(map_err)?.is_none() {
+                return Ok(false);
             }
+            storage.remove_item(key).map_err(map_err)?;
+            storage
+                .remove_item(&format!("{}{}", key, META_SUFFIX))
+                .map_err(map_err)?;
+
+            Ok(true)
+        })
+    }
 
-            let Some(json) = storage.get_item(&key).map_err(map_err)? else {
-                continue;
+    fn rename<'a>(&'a self, from: &'a str, to: &'a str) -> BoxedFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            let storage = self.storage()?;
+
+            let Some(item) = storage.get_item(from).map_err(map_err)? else {
+                return Ok(false);
+            };
+            storage.set_item(to, &item).map_err(map_err)?;
+            storage.remove_item(from).map_err(map_err)?;
+
+            // Keep the modification time of the original value.
+            let from_metadata_key = format!("{}{}", from, META_SUFFIX);
+            let mut metadata = match storage.get_item(&from_metadata_key).map_err(map_err)? {
+                Some(json) => serde_json::from_str(&json).map_err(|error| {
+                    format!("failed to parse JSON at '{}': {error}", from_metadata_key)
+                })?,
+                None => Metadata::new(""),
             };
-            let metadata = serde_json::from_str(&json)
-                .map_err(|error| format!("failed to parse JSON at '{}': {error}", key))?;
-            results.push(metadata)
-        }
+            metadata.name = file_stem(to).into();
+            let metadata_json = serde_json::to_string(&metadata)
+                .map_err(|err| BevyError::from(format!("failed to serialize JSON: {err}")))?;
+            storage
+                .set_item(&format!("{}{}", to, META_SUFFIX), &metadata_json)
+                .map_err(map_err)?;
+            storage.remove_item(&from_metadata_key).map_err(map_err)?;
+
+            Ok(true)
+        })
+    }
 
-        Ok(results)
+    fn list<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Vec<Metadata>>> {
+        Box::pin(async move {
+            let storage = self.storage()?;
+            let prefix = if key.is_empty() {
+                String::new()
+            } else {
+                format!("{key}/")
+            };
+            let length = storage.length().map_err(map_err)?;
+
+            let mut results = Vec::new();
+            for i in 0..length {
+                let Some(key) = storage.key(i).map_err(map_err)? else {
+                    continue;
+                };
+                if !key.starts_with(&prefix) || !key.ends_with(META_SUFFIX) {
+                    continue;
+                }
+
+                let Some(json) = storage.get_item(&key).map_err(map_err)? else {
+                    continue;
+                };
+                let mut metadata: Metadata = serde_json::from_str(&json)
+                    .map_err(|error| format!("failed to parse JSON at '{}': {error}", key))?;
+                metadata.key = key.trim_end_matches(META_SUFFIX).into();
+                results.push(metadata)
+            }
+
+            Ok(results)
+        })
     }
 }
 
-impl Store {
+impl LocalStorageBackend {
     fn storage(&self) -> Result<web_sys::Storage> {
         Ok(web_sys::window()
             .ok_or("failed to get window")?
@@ -107,9 +156,83 @@ impl Store {
     }
 }
 
-fn file_stem(path: &str) -> &str {
-    let name = path.rsplit_once('/').map(|(_, s)| s).unwrap_or(path);
-    name.rsplit_once('.').map(|(s, _)| s).unwrap_or(name)
+/// Gives a file to the user as a browser download. Returns the name of the downloaded file.
+/// Browsers choose where downloads are written, so `path` is ignored.
+pub async fn export_file(_path: &str, file_name: &str, bytes: Vec<u8>) -> Result<String> {
+    let window = web_sys::window().ok_or("failed to get window")?;
+    let document = window.document().ok_or("failed to get document")?;
+
+    let parts = Array::of1(&Uint8Array::from(bytes.as_slice()));
+    let blob = Blob::new_with_u8_array_sequence(&parts).map_err(map_err)?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(map_err)?;
+
+    let link: HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(map_err)?
+        .dyn_into()
+        .map_err(map_err)?;
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(
+            revoke.unchecked_ref(),
+            EXPORT_URL_LIFETIME_MS,
+        )
+        .map_err(map_err)?;
+    Ok(file_name.to_owned())
+}
+
+/// Asks the user to upload a file. Returns the file name and contents, or `None` if the user
+/// cancelled. Browsers don't allow reading arbitrary paths, so `path` is ignored.
+pub async fn import_file(_path: &str) -> Result<Option<(String, Vec<u8>)>> {
+    let document = web_sys::window()
+        .ok_or("failed to get window")?
+        .document()
+        .ok_or("failed to get document")?;
+
+    let input: HtmlInputElement = document
+        .create_element("input")
+        .map_err(map_err)?
+        .dyn_into()
+        .map_err(map_err)?;
+    input.set_type("file");
+    input.set_accept(&format!(".{}", crate::Archive::EXTENSION));
+
+    let chosen = Promise::new(&mut |resolve, _| {
+        let on_change = Closure::once_into_js({
+            let resolve = resolve.clone();
+            move || {
+                let _ = resolve.call0(&JsValue::NULL);
+            }
+        });
+        let on_cancel = Closure::once_into_js(move || {
+            let _ = resolve.call0(&JsValue::NULL);
+        });
+        input.set_onchange(Some(on_change.unchecked_ref()));
+        let _ = input.add_event_listener_with_callback("cancel", on_cancel.unchecked_ref());
+    });
+    input.click();
+    JsFuture::from(chosen).await.map_err(map_err)?;
+
+    let Some(file) = input.files().and_then(|files| files.get(0)) else {
+        return Ok(None);
+    };
+    let buffer = JsFuture::from(file.array_buffer()).await.map_err(map_err)?;
+    Ok(Some((file.name(), Uint8Array::new(&buffer).to_vec())))
+}
+
+/// Whether the value at a key is JSON, which can be stored as-is. Anything else, such as binary
+/// saves and thumbnails, is stored as base64.
+fn is_text(key: &str) -> bool {
+    match key.rsplit_once('.') {
+        Some((_, extension)) if !extension.contains('/') => extension == "json",
+        _ => true,
+    }
 }
 
 fn map_err(err: JsValue) -> BevyError {
@@ -1,6 +1,6 @@
 use bevy::{ecs::world::OnDespawn, prelude::*};
 use pb_assets::AssetHandles;
-use pb_engine::pawn::ai::path::PathQuery;
+use pb_engine::pawn::ai::{group, path::PathQuery};
 use pb_render::pawn::PawnHighlight;
 
 use crate::{
@@ -9,6 +9,7 @@ use crate::{
         physics::pawn::{CancelPawn, ClickPawn, SelectPawn},
         point::ClickPoint,
     },
+    pawn_info::Selected,
     theme::Theme,
 };
 
@@ -23,10 +24,13 @@ pub struct DefaultAction {
 enum DefaultActionState {
     #[default]
     Default,
-    SelectedPawn {
-        pawn: Entity,
-        highlight: Entity,
-    },
+    SelectedPawns(Vec<SelectedPawn>),
+}
+
+#[derive(Debug)]
+struct SelectedPawn {
+    pawn: Entity,
+    highlight: Entity,
 }
 
 pub fn spawn(mut commands: Commands) {
@@ -74,7 +78,7 @@ fn click_pawn(
     assets: Res<AssetHandles>,
     theme: Res<Theme>,
 ) -> Result {
-    action.click_pawn(trigger.pawn, &mut commands, &assets, &theme)
+    action.click_pawn(trigger.pawn, trigger.extend, &mut commands, &assets, &theme)
 }
 
 fn click_point(
@@ -120,37 +124,51 @@ impl DefaultAction {
     fn click_pawn(
         &mut self,
         pawn: Entity,
+        extend: bool,
         commands: &mut Commands,
         assets: &AssetHandles,
         theme: &Theme,
     ) -> Result {
-        if !self.is_selected(pawn) {
+        if extend {
+            if self.is_selected(pawn) {
+                self.deselect(pawn, commands);
+                return Ok(());
+            }
+        } else if self.is_selected(pawn) && self.selected().len() == 1 {
+            return Ok(());
+        } else {
             self.cancel(commands)?;
+        }
 
-            let highlight = commands
-                .spawn(PawnHighlight::bundle(
-                    assets,
-                    pawn,
-                    theme.accent.with_alpha(0.88),
-                ))
-                .id();
-            self.state = DefaultActionState::SelectedPawn { pawn, highlight };
+        let highlight = commands
+            .spawn(PawnHighlight::bundle(
+                assets,
+                pawn,
+                theme.accent.with_alpha(0.88),
+            ))
+            .id();
+        commands.entity(pawn).insert(Selected);
+        let selected = SelectedPawn { pawn, highlight };
+        match &mut self.state {
+            DefaultActionState::SelectedPawns(pawns) => pawns.push(selected),
+            DefaultActionState::Default => {
+                self.state = DefaultActionState::SelectedPawns(vec![selected])
+            }
         }
 
         Ok(())
     }
 
     fn click_point(&mut self, commands: &mut Commands, path_q: &PathQuery, to: Vec2) -> Result {
-        match self.state {
-            DefaultActionState::Default => (),
-            DefaultActionState::SelectedPawn { pawn, .. } => {
-                info!("move {pawn} to {to}");
-                match path_q.path(pawn, to) {
-                    Some(path) => {
-                        commands.spawn(path);
-                    }
-                    None => warn!("no path found for {pawn} to {to}"),
-                }
+        let pawns: Vec<Entity> = self
+            .selected()
+            .iter()
+            .map(|selected| selected.pawn)
+            .collect();
+        if !pawns.is_empty() {
+            info!("move {pawns:?} to {to}");
+            if group::move_to(commands, path_q, &pawns, to).is_none() {
+                warn!("no path found for {pawns:?} to {to}");
             }
         }
 
@@ -160,17 +178,41 @@ impl DefaultAction {
     fn cancel(&mut self, commands: &mut Commands) -> Result {
         self.cancel_pawn(commands)?;
 
-        if let DefaultActionState::SelectedPawn { highlight, .. } = self.state {
-            commands.entity(highlight).despawn();
+        if let DefaultActionState::SelectedPawns(selected) = std::mem::take(&mut self.state) {
+            for selected in selected {
+                commands.entity(selected.pawn).try_remove::<Selected>();
+                commands.entity(selected.highlight).despawn();
+            }
         }
 
         Ok(())
     }
 
-    fn is_selected(&self, entity: Entity) -> bool {
-        match self.state {
-            DefaultActionState::Default => false,
-            DefaultActionState::SelectedPawn { pawn, .. } => pawn == entity,
+    fn deselect(&mut self, pawn: Entity, commands: &mut Commands) {
+        if let DefaultActionState::SelectedPawns(selected) = &mut self.state {
+            selected.retain(|selected| {
+                if selected.pawn == pawn {
+                    commands.entity(selected.pawn).try_remove::<Selected>();
+                    commands.entity(selected.highlight).despawn();
+                }
+                selected.pawn != pawn
+            });
+            if selected.is_empty() {
+                self.state = DefaultActionState::Default;
+            }
         }
     }
+
+    fn selected(&self) -> &[SelectedPawn] {
+        match &self.state {
+            DefaultActionState::Default => &[],
+            DefaultActionState::SelectedPawns(selected) => selected,
+        }
+    }
+
+    fn is_selected(&self, entity: Entity) -> bool {
+        self.selected()
+            .iter()
+            .any(|selected| selected.pawn == entity)
+    }
 }
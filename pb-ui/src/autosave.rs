@@ -5,12 +5,20 @@ use pb_engine::{EngineState, save::SaveParam};
 use pb_store::Store;
 use pb_util::callback::{CallbackSender, spawn_io};
 
-use crate::message::Message;
+use crate::{
+    input::Settings,
+    menu::saves::{save_key, write_save},
+    message::Message,
+};
 
-pub const INTERVAL: Duration = Duration::from_secs(5 * 60);
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// The shortest interval between autosaves, so a zero or tiny setting doesn't save every frame.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(30);
+pub const DEFAULT_BACKUPS: usize = 3;
 
 pub fn run_condition(
     state: Res<State<EngineState>>,
+    settings: Option<Res<Settings>>,
     time: Res<Time<Real>>,
     mut timer: Local<Option<Timer>>,
 ) -> bool {
@@ -18,18 +26,28 @@ pub fn run_condition(
         return false;
     }
 
-    let timer = timer.get_or_insert_with(|| Timer::new(INTERVAL, TimerMode::Repeating));
+    let interval = settings.map_or(DEFAULT_INTERVAL, |settings| settings.autosave_interval);
+    let timer = timer.get_or_insert_with(|| Timer::new(interval, TimerMode::Repeating));
+    if timer.duration() != interval {
+        timer.set_duration(interval);
+    }
     timer.tick(time.delta());
     timer.just_finished()
 }
 
-pub fn run(save_p: SaveParam, store: Res<Store>, callback: Res<CallbackSender>) -> Result {
+pub fn run(
+    save_p: SaveParam,
+    store: Res<Store>,
+    settings: Res<Settings>,
+    callback: Res<CallbackSender>,
+) -> Result {
     let scene = save_p.save()?;
+    let backups = settings.autosave_backups;
 
     let store = store.clone();
     let callback = callback.clone();
     spawn_io(async move {
-        let res = store.set("saves/autosave.json", scene).await;
+        let res = write_save(&store, &save_key("autosave"), scene, backups).await;
         callback.run_system_cached_with(on_save_complete, res);
     });
 
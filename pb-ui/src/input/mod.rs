@@ -12,6 +12,7 @@ use bevy::{
     prelude::*,
 };
 use movement::{MovementDirection, MovementInput};
+use pb_engine::clock::Speed;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -31,6 +32,9 @@ pub enum Input {
     MoveRight,
     MoveBackward,
     TogglePause,
+    SpeedNormal,
+    SpeedFast,
+    SpeedFastest,
 }
 
 #[derive(Event, Debug, Clone, Copy)]
@@ -61,6 +65,11 @@ pub enum GridInput {
 #[derive(Event, Debug, Clone, Copy)]
 pub struct TogglePauseInput;
 
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SpeedInput {
+    pub speed: Speed,
+}
+
 pub fn read(
     mut commands: Commands,
     settings: Res<Settings>,
@@ -140,6 +149,25 @@ pub fn read(
                         commands.trigger(TogglePauseInput);
                     }
                 }
+                Input::SpeedNormal => {
+                    if event.state == ButtonState::Released {
+                        commands.trigger(SpeedInput {
+                            speed: Speed::Normal,
+                        });
+                    }
+                }
+                Input::SpeedFast => {
+                    if event.state == ButtonState::Released {
+                        commands.trigger(SpeedInput { speed: Speed::Fast });
+                    }
+                }
+                Input::SpeedFastest => {
+                    if event.state == ButtonState::Released {
+                        commands.trigger(SpeedInput {
+                            speed: Speed::Fastest,
+                        });
+                    }
+                }
             }
         }
     }
@@ -1,11 +1,12 @@
 use bevy::prelude::*;
+use pb_engine::clock::Clock;
 
-use crate::input::TogglePauseInput;
+use crate::input::{SpeedInput, TogglePauseInput};
 
-pub fn input(_: Trigger<TogglePauseInput>, mut time: ResMut<Time<Virtual>>) {
-    if time.is_paused() {
-        time.unpause();
-    } else {
-        time.pause();
-    }
+pub fn input(_: Trigger<TogglePauseInput>, mut clock: ResMut<Clock>) {
+    clock.toggle_paused();
+}
+
+pub fn speed_input(trigger: Trigger<SpeedInput>, mut clock: ResMut<Clock>) {
+    clock.set_speed(trigger.speed);
 }
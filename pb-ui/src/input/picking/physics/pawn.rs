@@ -15,6 +15,8 @@ pub struct CancelPawn {
 #[derive(Event, Debug, Clone, Copy)]
 pub struct ClickPawn {
     pub pawn: Entity,
+    /// Whether to add the pawn to the current selection, rather than replacing it.
+    pub extend: bool,
 }
 
 pub fn pawn_added(trigger: Trigger<OnAdd, Pawn>, mut commands: Commands) {
@@ -41,12 +43,17 @@ fn out(mut trigger: Trigger<Pointer<Out>>, mut commands: Commands) {
     });
 }
 
-fn click(mut trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+fn click(
+    mut trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    keyboard_state: Res<ButtonInput<KeyCode>>,
+) {
     trigger.propagate(false);
 
     if trigger.button == PointerButton::Primary {
         commands.trigger(ClickPawn {
             pawn: trigger.target(),
+            extend: keyboard_state.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
         });
     }
 }
@@ -1,14 +1,19 @@
+use std::time::Duration;
+
 use bevy::{platform::collections::HashMap, prelude::*};
 use pb_store::Store;
 use serde::{Deserialize, Serialize};
 
-use crate::input::Input;
+use crate::{autosave, input::Input};
 
 pub const KEY: &str = "settings";
 
 #[derive(Resource)]
 pub struct Settings {
     pub binds: HashMap<KeyCode, Vec<Binding>>,
+    pub autosave_interval: Duration,
+    /// The number of previous autosaves kept as backups.
+    pub autosave_backups: usize,
 }
 
 pub struct Binding {
@@ -19,6 +24,10 @@ pub struct Binding {
 #[derive(Serialize, Deserialize, TypePath)]
 pub struct SettingsModel {
     binds: HashMap<Input, BindingModel>,
+    #[serde(default = "default_autosave_interval_secs")]
+    autosave_interval_secs: u64,
+    #[serde(default = "default_autosave_backups")]
+    autosave_backups: usize,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -33,6 +42,8 @@ impl Settings {
     pub fn empty() -> Self {
         Settings {
             binds: HashMap::default(),
+            autosave_interval: autosave::DEFAULT_INTERVAL,
+            autosave_backups: autosave::DEFAULT_BACKUPS,
         }
     }
 
@@ -83,6 +94,9 @@ impl Default for Settings {
         settings.bind(KeyCode::ArrowRight, Input::MoveRight, vec![]);
         settings.bind(KeyCode::ArrowDown, Input::MoveBackward, vec![]);
         settings.bind(KeyCode::KeyP, Input::TogglePause, vec![]);
+        settings.bind(KeyCode::Digit1, Input::SpeedNormal, vec![]);
+        settings.bind(KeyCode::Digit2, Input::SpeedFast, vec![]);
+        settings.bind(KeyCode::Digit3, Input::SpeedFastest, vec![]);
         settings
     }
 }
@@ -93,6 +107,67 @@ impl From<SettingsModel> for Settings {
         for (action, bind) in model.binds {
             settings.bind(bind.key, action, bind.modifiers);
         }
+        settings.autosave_interval =
+            Duration::from_secs(model.autosave_interval_secs).max(autosave::MIN_INTERVAL);
+        settings.autosave_backups = model.autosave_backups;
         settings
     }
 }
+
+fn default_autosave_interval_secs() -> u64 {
+    autosave::DEFAULT_INTERVAL.as_secs()
+}
+
+fn default_autosave_backups() -> usize {
+    autosave::DEFAULT_BACKUPS
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::tasks::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let store = Store::memory();
+
+        block_on(async {
+            let settings = Settings::load(&store).await;
+            assert!(!settings.get_bind(KeyCode::Escape).is_empty());
+
+            let model = SettingsModel {
+                binds: HashMap::from_iter([(
+                    Input::Cancel,
+                    BindingModel {
+                        key: KeyCode::KeyX,
+                        modifiers: vec![],
+                    },
+                )]),
+                autosave_interval_secs: 60,
+                autosave_backups: default_autosave_backups(),
+            };
+            store.set(KEY, model).await.unwrap();
+
+            let settings = Settings::load(&store).await;
+            assert!(settings.get_bind(KeyCode::Escape).is_empty());
+            assert!(
+                settings
+                    .get_bind(KeyCode::KeyX)
+                    .iter()
+                    .any(|bind| bind.action == Input::Cancel)
+            );
+            assert_eq!(settings.autosave_interval, Duration::from_secs(60));
+
+            let model = SettingsModel {
+                binds: HashMap::default(),
+                autosave_interval_secs: 0,
+                autosave_backups: default_autosave_backups(),
+            };
+            store.set(KEY, model).await.unwrap();
+
+            let settings = Settings::load(&store).await;
+            assert_eq!(settings.autosave_interval, autosave::MIN_INTERVAL);
+        });
+    }
+}
@@ -8,6 +8,7 @@ pub struct Layout {
     pub menu: Entity,
     pub ribbon: Entity,
     pub messages: Entity,
+    pub pawn_info: Entity,
 }
 
 pub fn init(mut commands: Commands, theme: Res<Theme>) {
@@ -37,10 +38,16 @@ pub fn init(mut commands: Commands, theme: Res<Theme>) {
 
     let messages = builder.messages().named("pb_ui::layout::messages").id();
 
+    let pawn_info = builder
+        .pawn_info_root()
+        .named("pb_ui::layout::pawn_info")
+        .id();
+
     commands.insert_resource(Layout {
         root,
         menu,
         ribbon,
         messages,
+        pawn_info,
     })
 }
@@ -7,6 +7,7 @@ mod layout;
 mod loading;
 mod menu;
 mod message;
+mod pawn_info;
 mod ribbon;
 mod startup;
 mod theme;
@@ -64,6 +65,7 @@ impl Plugin for PbUiPlugin {
                     widget::disabled::update,
                     widget::spinner::update,
                     widget::input::update.after(TextInputSystem),
+                    menu::saves::update_lists,
                 ),
             )
             .add_observer(input::cancel::cancellable_added)
@@ -82,10 +84,22 @@ impl Plugin for PbUiPlugin {
 
         app.add_computed_state::<RibbonState>()
             .add_systems(OnEnter(RibbonState::Shown), ribbon::show)
-            .add_systems(OnEnter(RibbonState::Hidden), ribbon::hide);
+            .add_systems(OnEnter(RibbonState::Hidden), ribbon::hide)
+            .add_systems(Update, ribbon::clock::update);
 
-        app.add_event::<Message>()
-            .add_systems(Update, (message::spawn_messages, message::despawn_messages));
+        app.add_event::<Message>().add_systems(
+            Update,
+            (
+                message::security_messages.before(message::spawn_messages),
+                message::spawn_messages,
+                message::despawn_messages,
+            ),
+        );
+
+        app.add_systems(
+            Update,
+            (pawn_info::update_panel, pawn_info::update_text).chain(),
+        );
 
         app.add_systems(PostUpdate, autosave::run.run_if(autosave::run_condition));
 
@@ -112,6 +126,7 @@ impl Plugin for PbUiPlugin {
             .add_observer(input::camera::input)
             .add_observer(input::movement::input)
             .add_observer(input::pause::input)
+            .add_observer(input::pause::speed_input)
             .add_observer(input::picking::point::grid::input)
             .add_observer(input::picking::point::root_added)
             .add_observer(input::picking::point::grid::grid_added)
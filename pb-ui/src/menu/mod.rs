@@ -1,4 +1,4 @@
-mod saves;
+pub mod saves;
 
 use bevy::{app::AppExit, prelude::*};
 
@@ -1,30 +1,36 @@
+use std::{cmp::Ordering, iter, time::Duration};
+
 use bevy::{
     ecs::{
         error::HandleError,
         system::command::{run_system_cached, run_system_cached_with},
         world::CommandQueue,
     },
+    platform::collections::HashMap,
     prelude::*,
 };
+use bevy_simple_text_input::TextInputValue;
 use pb_engine::{
     EngineState,
-    save::{SaveModel, SaveParam},
+    save::{SaveInfo, SaveModel, SaveParam, VersionedSave},
 };
-use pb_store::{Metadata, Store};
+use pb_store::{Archive, Format, Metadata, Sidecar, Store};
 
 use pb_assets::AssetHandles;
+use pb_render::thumbnail;
 use pb_util::callback::{CallbackSender, spawn_io};
 use smol_str::SmolStr;
 
 use crate::{
     UiState,
+    input::Settings,
     layout::Layout,
     menu::MenuPanel,
     message::Message,
     theme::Theme,
     widget::{
         UiBuilder,
-        form::{self, Form, FormField, FormSubmit},
+        form::{self, Form, FormField, FormSubmit, FormUpdate},
     },
 };
 
@@ -34,14 +40,92 @@ enum SaveAction {
     Load,
 }
 
+#[derive(Component, Clone)]
+struct SaveItem {
+    list: Entity,
+    metadata: Metadata,
+}
+
+/// The saves shown in a saves table, along with how they are sorted and filtered.
+#[derive(Component)]
+pub struct SaveList {
+    action: SaveAction,
+    items: Vec<(Metadata, Option<SaveInfo>)>,
+    sort: SaveSort,
+    descending: bool,
+    filter: String,
+    editing: Option<(SmolStr, SaveEdit)>,
+    thumbnails: HashMap<SmolStr, Handle<Image>>,
+}
+
+/// The saves in the store, along with their decoded thumbnails.
+#[derive(Clone)]
+struct SaveListing {
+    items: Vec<(Metadata, Option<SaveInfo>)>,
+    thumbnails: Vec<(SmolStr, Image)>,
+}
+
+/// The container which the rows of a [`SaveList`] are rendered into.
+#[derive(Component)]
+pub struct SaveListRows(Entity);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SaveSort {
+    Name,
+    Modified,
+    Date,
+    Pawns,
+    PlayTime,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SaveEdit {
+    Rename,
+    Delete,
+    Export,
+}
+
 #[derive(Component)]
-struct SaveItem(Metadata);
+struct SortButton {
+    list: Entity,
+    sort: SaveSort,
+}
+
+#[derive(Debug, Clone, Reflect)]
+struct RenameForm {
+    name: String,
+}
 
 #[derive(Debug, Clone, Reflect)]
 struct SaveForm {
     name: String,
 }
 
+#[derive(Debug, Clone, Reflect)]
+struct ImportForm {
+    path: String,
+}
+
+#[derive(Debug, Clone, Reflect)]
+struct ExportForm {
+    path: String,
+}
+
+/// A button which exports a save to the path entered in an [`ExportForm`].
+#[derive(Component)]
+struct ExportButton {
+    form: Entity,
+    kind: ExportKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportKind {
+    /// An archive of the save and its sidecars, which can be imported.
+    Archive,
+    /// The save document alone, as JSON.
+    Json,
+}
+
 pub fn save_panel_button(
     _: Trigger<Pointer<Click>>,
     mut commands: Commands,
@@ -119,8 +203,10 @@ fn load_button(
     mut next_engine_state: ResMut<NextState<EngineState>>,
     callback: Res<CallbackSender>,
     store: Res<Store>,
+    settings: Res<Settings>,
 ) -> Result {
-    let save_name = save_q.get(trigger.target())?.0.name.clone();
+    let key = save_q.get(trigger.target())?.metadata.key.clone();
+    let backups = settings.autosave_backups;
 
     if let &EngineState::Running(root) = engine_state.get() {
         commands.entity(root).despawn();
@@ -133,8 +219,9 @@ fn load_button(
     let callback = callback.clone();
     spawn_io(async move {
         let res = store
-            .get::<SaveModel>(&format!("saves/{save_name}.json"))
-            .await;
+            .get_with_backups::<VersionedSave>(&key, backups)
+            .await
+            .map(|save| save.0);
         callback.run_system_cached_with(on_load_complete, res);
     });
 
@@ -172,8 +259,65 @@ fn overwrite_button(
     store: Res<Store>,
     callback: Res<CallbackSender>,
 ) -> Result {
-    let save_name = save_q.get(trigger.target())?.0.name.clone();
-    save_impl(save_name, save_p, store.clone(), callback.clone())
+    let save = &save_q.get(trigger.target())?.metadata;
+    save_impl(
+        save.name.clone(),
+        save.key.clone(),
+        save_p,
+        store.clone(),
+        callback.clone(),
+    )
+}
+
+fn export_edit_button(
+    trigger: Trigger<Pointer<Click>>,
+    save_q: Query<&SaveItem>,
+    list_q: Query<&mut SaveList>,
+) -> Result {
+    start_edit(trigger, save_q, list_q, SaveEdit::Export)
+}
+
+fn export_button(
+    trigger: Trigger<Pointer<Click>>,
+    button_q: Query<(&ExportButton, &SaveItem)>,
+    form_q: Query<&Form>,
+    store: Res<Store>,
+    callback: Res<CallbackSender>,
+) -> Result {
+    let (button, item) = button_q.get(trigger.target())?;
+    let path = form_q.get(button.form)?.value::<ExportForm>()?.path;
+    let name = item.metadata.name.clone();
+    let key = item.metadata.key.clone();
+    let kind = button.kind;
+
+    let store = store.clone();
+    let callback = callback.clone();
+    spawn_io(async move {
+        let res = export_save(&store, &key, &name, &path, kind).await;
+        callback.run_system_cached_with(on_export_complete, res);
+    });
+
+    fn on_export_complete(
+        In(res): In<Result<String>>,
+        mut list_q: Query<&mut SaveList>,
+        mut message_e: EventWriter<Message>,
+    ) {
+        match res {
+            Ok(path) => {
+                info!("Successfully exported save to '{path}'");
+                message_e.write(Message::info(format!("Exported save to '{path}'")));
+                for mut list in &mut list_q {
+                    list.editing = None;
+                }
+            }
+            Err(error) => {
+                error!("Failed to export save: {error}");
+                message_e.write(Message::error(&error));
+            }
+        }
+    }
+
+    Ok(())
 }
 
 fn save_button(
@@ -186,10 +330,83 @@ fn save_button(
     trigger.propagate(false);
 
     let save_name = form_q.get(trigger.target())?.value::<SaveForm>()?.name;
-    save_impl(save_name.into(), save_p, store.clone(), callback.clone())
+    let key = save_key(&save_name);
+    save_impl(
+        save_name.into(),
+        key,
+        save_p,
+        store.clone(),
+        callback.clone(),
+    )
 }
 
-fn save_impl(name: SmolStr, save_p: SaveParam, store: Store, callback: CallbackSender) -> Result {
+fn import_submit(
+    mut trigger: Trigger<FormSubmit>,
+    form_q: Query<&Form>,
+    store: Res<Store>,
+    callback: Res<CallbackSender>,
+) -> Result {
+    trigger.propagate(false);
+
+    let path = form_q.get(trigger.target())?.value::<ImportForm>()?.path;
+
+    let store = store.clone();
+    let callback = callback.clone();
+    spawn_io(async move {
+        match import_save(&store, &path).await {
+            Ok(Some(message)) => callback.run_system_cached_with(on_manage_complete, Ok(message)),
+            Ok(None) => {}
+            Err(error) => callback.run_system_cached_with(on_manage_complete, Err(error)),
+        }
+    });
+
+    Ok(())
+}
+
+/// The key of a new save with the given name, which is stored in the binary format.
+pub fn save_key(name: &str) -> SmolStr {
+    format!("saves/{name}.{}", Format::BINARY_EXTENSION).into()
+}
+
+/// Stores a save along with its info and thumbnail sidecars, keeping up to `backups` of the
+/// previous versions.
+pub async fn write_save(store: &Store, key: &str, save: SaveModel, backups: usize) -> Result {
+    let info = save.info();
+    let thumbnail = thumbnail::render(&save);
+    store
+        .set_with_backups(key, VersionedSave(save), backups)
+        .await?;
+    store.set(&Sidecar::Info.key(key), info).await?;
+
+    // The save is still usable without a thumbnail, so failing to create one isn't fatal.
+    match thumbnail {
+        Ok(png) => store.set_bytes(&Sidecar::Thumbnail.key(key), png).await?,
+        Err(error) => warn!("Failed to render thumbnail for '{key}': {error}"),
+    }
+    Ok(())
+}
+
+/// Stores a save at `key`, then removes any other save with the same name, such as one stored in
+/// a different format by an older version.
+async fn replace_save(store: &Store, name: &str, key: &str, save: SaveModel) -> Result {
+    write_save(store, key, save, 0).await?;
+
+    for metadata in store.iter("saves").await? {
+        if metadata.name == name && metadata.key != key {
+            info!("Replacing '{}' with '{key}'", metadata.key);
+            store.delete(&metadata.key).await?;
+        }
+    }
+    Ok(())
+}
+
+fn save_impl(
+    name: SmolStr,
+    key: SmolStr,
+    save_p: SaveParam,
+    store: Store,
+    callback: CallbackSender,
+) -> Result {
     let scene = save_p.save()?;
 
     let store = store.clone();
@@ -198,7 +415,7 @@ fn save_impl(name: SmolStr, save_p: SaveParam, store: Store, callback: CallbackS
         let res = if name.is_empty() {
             Err("empty name".into())
         } else {
-            store.set(&format!("saves/{name}.json"), scene).await
+            replace_save(&store, &name, &key, scene).await
         };
 
         let mut queue = CommandQueue::default();
@@ -269,6 +486,31 @@ impl<'w> UiBuilder<'w, '_> {
     ) -> UiBuilder<'w, '_> {
         let mut panel = self.menu_panel(theme, assets, "Load prison");
         panel.saves_table(theme, store, callback, SaveAction::Load);
+
+        let mut import_form = panel.form(
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                column_gap: theme.gutter,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ImportForm {
+                path: String::new(),
+            },
+        );
+        import_form.observe(import_submit);
+
+        // Browsers can't read arbitrary paths, so the file is chosen when importing instead.
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            import_form.spawn((Text::new("Path"), theme.normal_text.clone()));
+            import_form.input(theme).insert(FormField::new("path"));
+        }
+        import_form
+            .button(theme, assets, "Import", default())
+            .on_click(form::submit);
+
         panel
     }
 
@@ -280,6 +522,8 @@ impl<'w> UiBuilder<'w, '_> {
         action: SaveAction,
     ) -> UiBuilder<'w, '_> {
         let mut container = self.container(Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
             flex_grow: 1.,
             align_self: AlignSelf::Stretch,
             min_width: Val::Px(425.),
@@ -290,15 +534,16 @@ impl<'w> UiBuilder<'w, '_> {
         container.spinner(theme, theme.large_icon_size_px);
 
         spawn_io(async move {
-            let res = store.iter("saves").await;
+            let res = list_saves(&store).await;
             callback.run_system_cached_with(on_list_complete, (res, container_id, action));
         });
 
         fn on_list_complete(
-            In((res, container_id, action)): In<(Result<Vec<Metadata>>, Entity, SaveAction)>,
+            In((res, container_id, action)): In<(Result<SaveListing>, Entity, SaveAction)>,
             mut commands: Commands,
             theme: Res<Theme>,
             assets: Res<AssetHandles>,
+            mut images: ResMut<Assets<Image>>,
         ) {
             let Ok(mut container) = commands.get_entity(container_id) else {
                 return;
@@ -308,8 +553,29 @@ impl<'w> UiBuilder<'w, '_> {
             builder.clear();
 
             match res {
-                Ok(items) => {
-                    builder.saves_table_items(&theme, &assets, items, action);
+                Ok(listing) => {
+                    let mut filter = builder.container(Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        column_gap: theme.gutter,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::bottom(theme.gutter),
+                        ..default()
+                    });
+                    filter.spawn((Text::new("Filter"), theme.normal_text.clone()));
+                    filter.input(&theme).insert(FormField::new("filter"));
+
+                    let rows = builder
+                        .container(Node {
+                            width: Val::Percent(100.),
+                            ..default()
+                        })
+                        .id();
+                    let mut list = SaveList::new(action);
+                    list.set_listing(listing, &mut images);
+                    builder
+                        .insert((list, SaveListRows(rows)))
+                        .observe(filter_changed);
                 }
                 Err(error) => {
                     error!("failed to load saves: {error}");
@@ -334,13 +600,14 @@ impl<'w> UiBuilder<'w, '_> {
         &mut self,
         theme: &Theme,
         assets: &AssetHandles,
-        items: Vec<Metadata>,
-        action: SaveAction,
+        list_id: Entity,
+        list: &SaveList,
     ) -> UiBuilder<'w, '_> {
         let mut container = self.container(Node {
             display: Display::Grid,
             width: Val::Percent(100.),
             grid_template_columns: vec![
+                GridTrack::auto(),
                 GridTrack::minmax(
                     MinTrackSizingFunction::Auto,
                     MaxTrackSizingFunction::Fraction(1.),
@@ -350,6 +617,9 @@ impl<'w> UiBuilder<'w, '_> {
                     MaxTrackSizingFunction::Fraction(2.),
                 ),
                 GridTrack::auto(),
+                GridTrack::auto(),
+                GridTrack::auto(),
+                GridTrack::auto(),
             ],
             grid_auto_rows: vec![GridTrack::max_content()],
             row_gap: theme.gutter,
@@ -358,75 +628,792 @@ impl<'w> UiBuilder<'w, '_> {
             ..default()
         });
 
-        container.spawn((
-            Text::new("Name"),
-            theme.emphasis_text.clone(),
-            Node {
-                grid_row: GridPlacement::start(1),
+        for (column, sort) in SaveSort::ALL.into_iter().enumerate() {
+            let label = if sort != list.sort {
+                sort.label().to_owned()
+            } else if list.descending {
+                format!("{} v", sort.label())
+            } else {
+                format!("{} ^", sort.label())
+            };
+
+            container
+                .button(
+                    theme,
+                    assets,
+                    label,
+                    Node {
+                        grid_row: GridPlacement::start(1),
+                        grid_column: GridPlacement::start(column as i16 + 2),
+                        ..default()
+                    },
+                )
+                .on_click(sort_button)
+                .insert(SortButton {
+                    list: list_id,
+                    sort,
+                });
+        }
+
+        for (row, (metadata, info)) in list.visible_items().into_iter().enumerate() {
+            let grid_row = GridPlacement::start(row as i16 + 2);
+            let item = SaveItem {
+                list: list_id,
+                metadata: metadata.clone(),
+            };
+            let editing = list
+                .editing
+                .as_ref()
+                .filter(|(key, _)| *key == metadata.key)
+                .map(|&(_, edit)| edit);
+
+            let mut thumbnail = container.spawn(Node {
+                grid_row,
                 grid_column: GridPlacement::start(1),
+                width: Val::Px(thumbnail::WIDTH as f32 / 2.),
+                height: Val::Px(thumbnail::HEIGHT as f32 / 2.),
                 ..default()
-            },
-        ));
-        container.spawn((
-            Text::new("Modified"),
-            theme.emphasis_text.clone(),
-            Node {
-                grid_row: GridPlacement::start(1),
-                grid_column: GridPlacement::start(2),
+            });
+            if let Some(image) = list.thumbnails.get(&metadata.key) {
+                thumbnail.insert(ImageNode::new(image.clone()));
+            }
+
+            let mut export_form = None;
+            if editing == Some(SaveEdit::Rename) {
+                let mut rename_form = container.form(
+                    Node {
+                        grid_row,
+                        grid_column: GridPlacement::start(2),
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        column_gap: theme.gutter,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    RenameForm {
+                        name: metadata.name.to_string(),
+                    },
+                );
+                rename_form.observe(rename_submit).insert(item.clone());
+
+                rename_form.input(theme).insert((
+                    FormField::new("name"),
+                    TextInputValue(metadata.name.to_string()),
+                ));
+                rename_form
+                    .button(theme, assets, "OK", default())
+                    .on_click(form::submit);
+            } else if editing == Some(SaveEdit::Export) {
+                let mut form = container.form(
+                    Node {
+                        grid_row,
+                        grid_column: GridPlacement::start(2),
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        column_gap: theme.gutter,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ExportForm {
+                        path: String::new(),
+                    },
+                );
+                form.insert(item.clone());
+
+                // Browsers choose where downloads go, so there is no path to enter. On native, an
+                // empty path exports to the downloads directory.
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    form.spawn((Text::new("Path"), theme.normal_text.clone()));
+                    form.input(theme).insert(FormField::new("path"));
+                }
+                export_form = Some(form.id());
+            } else {
+                container.spawn((
+                    Text::new(metadata.name.clone()),
+                    theme.normal_text.clone(),
+                    Node {
+                        grid_row,
+                        grid_column: GridPlacement::start(2),
+                        ..default()
+                    },
+                ));
+            }
+
+            let info_text = match info {
+                Some(info) => [
+                    info.clock.to_string(),
+                    info.pawns.to_string(),
+                    format_play_time(info.clock.play_time()),
+                ],
+                None => ["-".to_owned(), "-".to_owned(), "-".to_owned()],
+            };
+            let texts = iter::once(metadata.modified_local().to_rfc2822()).chain(info_text);
+            for (column, text) in texts.enumerate() {
+                container.spawn((
+                    Text::new(text),
+                    theme.normal_text.clone(),
+                    Node {
+                        grid_row,
+                        grid_column: GridPlacement::start(column as i16 + 3),
+                        ..default()
+                    },
+                ));
+            }
+
+            let mut actions = container.container(Node {
+                grid_row,
+                grid_column: GridPlacement::start(7),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                column_gap: theme.gutter,
                 ..default()
-            },
-        ));
-
-        for (row, item) in items.into_iter().enumerate() {
-            container.spawn((
-                Text::new(item.name.clone()),
-                theme.normal_text.clone(),
-                Node {
-                    grid_row: GridPlacement::start(row as i16 + 2),
-                    grid_column: GridPlacement::start(1),
-                    ..default()
-                },
-            ));
-            container.spawn((
-                Text::new(item.modified_local().to_rfc2822()),
-                theme.normal_text.clone(),
-                Node {
-                    grid_row: GridPlacement::start(row as i16 + 2),
-                    grid_column: GridPlacement::start(2),
-                    ..default()
-                },
-            ));
-
-            match action {
-                SaveAction::Save => container
-                    .button(
-                        theme,
-                        assets,
-                        "Overwrite",
-                        Node {
-                            grid_row: GridPlacement::start(row as i16 + 2),
-                            grid_column: GridPlacement::start(3),
-                            ..default()
-                        },
-                    )
+            });
+
+            match editing {
+                Some(SaveEdit::Delete) => {
+                    actions
+                        .button(theme, assets, "Confirm delete", default())
+                        .on_click(confirm_delete_button)
+                        .insert(item.clone());
+                    actions
+                        .button(theme, assets, "Cancel", default())
+                        .on_click(cancel_edit_button)
+                        .insert(item);
+                    continue;
+                }
+                Some(SaveEdit::Rename) => {
+                    actions
+                        .button(theme, assets, "Cancel", default())
+                        .on_click(cancel_edit_button)
+                        .insert(item);
+                    continue;
+                }
+                Some(SaveEdit::Export) => {
+                    let form = export_form.unwrap();
+                    for (label, kind) in [
+                        ("Export archive", ExportKind::Archive),
+                        ("Export JSON", ExportKind::Json),
+                    ] {
+                        actions
+                            .button(theme, assets, label, default())
+                            .on_click(export_button)
+                            .insert((item.clone(), ExportButton { form, kind }));
+                    }
+                    actions
+                        .button(theme, assets, "Cancel", default())
+                        .on_click(cancel_edit_button)
+                        .insert(item);
+                    continue;
+                }
+                None => {}
+            }
+
+            match list.action {
+                SaveAction::Save => actions
+                    .button(theme, assets, "Overwrite", default())
                     .on_click(overwrite_button)
-                    .insert(SaveItem(item)),
-                SaveAction::Load => container
-                    .button(
-                        theme,
-                        assets,
-                        "Load",
-                        Node {
-                            grid_row: GridPlacement::start(row as i16 + 2),
-                            grid_column: GridPlacement::start(3),
-                            ..default()
-                        },
-                    )
+                    .insert(item.clone()),
+                SaveAction::Load => actions
+                    .button(theme, assets, "Load", default())
                     .on_click(load_button)
-                    .insert(SaveItem(item)),
+                    .insert(item.clone()),
             };
+            actions
+                .button(theme, assets, "Export", default())
+                .on_click(export_edit_button)
+                .insert(item.clone());
+            actions
+                .button(theme, assets, "Rename", default())
+                .on_click(rename_button)
+                .insert(item.clone());
+            actions
+                .button(theme, assets, "Duplicate", default())
+                .on_click(duplicate_button)
+                .insert(item.clone());
+            actions
+                .button(theme, assets, "Delete", default())
+                .on_click(delete_button)
+                .insert(item);
         }
 
         container
     }
 }
+
+impl SaveList {
+    fn new(action: SaveAction) -> Self {
+        SaveList {
+            action,
+            items: Vec::new(),
+            sort: SaveSort::Modified,
+            descending: true,
+            filter: String::new(),
+            editing: None,
+            thumbnails: HashMap::default(),
+        }
+    }
+
+    fn set_listing(&mut self, listing: SaveListing, images: &mut Assets<Image>) {
+        self.items = listing.items;
+        self.thumbnails = listing
+            .thumbnails
+            .into_iter()
+            .map(|(key, image)| (key, images.add(image)))
+            .collect();
+    }
+
+    /// The items matching the filter, in the current sort order.
+    fn visible_items(&self) -> Vec<&(Metadata, Option<SaveInfo>)> {
+        let filter = self.filter.to_lowercase();
+        let mut items: Vec<_> = self
+            .items
+            .iter()
+            .filter(|(metadata, _)| metadata.name.to_lowercase().contains(&filter))
+            .collect();
+
+        items.sort_by(|a, b| {
+            let ordering = self.sort.compare(a, b);
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        items
+    }
+}
+
+impl SaveSort {
+    const ALL: [SaveSort; 5] = [
+        SaveSort::Name,
+        SaveSort::Modified,
+        SaveSort::Date,
+        SaveSort::Pawns,
+        SaveSort::PlayTime,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SaveSort::Name => "Name",
+            SaveSort::Modified => "Modified",
+            SaveSort::Date => "Date",
+            SaveSort::Pawns => "Pawns",
+            SaveSort::PlayTime => "Play time",
+        }
+    }
+
+    fn compare(
+        self,
+        (a, a_info): &(Metadata, Option<SaveInfo>),
+        (b, b_info): &(Metadata, Option<SaveInfo>),
+    ) -> Ordering {
+        let a_info = a_info.as_ref();
+        let b_info = b_info.as_ref();
+        let ordering = match self {
+            SaveSort::Name => Ordering::Equal,
+            SaveSort::Modified => a.modified.cmp(&b.modified),
+            SaveSort::Date => a_info
+                .map(|info| info.clock.elapsed())
+                .cmp(&b_info.map(|info| info.clock.elapsed())),
+            SaveSort::Pawns => a_info
+                .map(|info| info.pawns)
+                .cmp(&b_info.map(|info| info.pawns)),
+            SaveSort::PlayTime => a_info
+                .map(|info| info.clock.play_time())
+                .cmp(&b_info.map(|info| info.clock.play_time())),
+        };
+        ordering.then_with(|| a.name.cmp(&b.name))
+    }
+}
+
+pub fn update_lists(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    assets: Res<AssetHandles>,
+    list_q: Query<(Entity, &SaveList, &SaveListRows), Changed<SaveList>>,
+) {
+    for (id, list, rows) in &list_q {
+        let mut builder = UiBuilder::new(commands.reborrow(), rows.0);
+        builder.clear();
+        builder.saves_table_items(&theme, &assets, id, list);
+    }
+}
+
+fn filter_changed(mut trigger: Trigger<FormUpdate>, mut list_q: Query<&mut SaveList>) -> Result {
+    if trigger.name != "filter" {
+        return Ok(());
+    }
+    trigger.propagate(false);
+
+    let filter = trigger
+        .value
+        .try_downcast_ref::<String>()
+        .ok_or("expected filter to be a string")?;
+    let mut list = list_q.get_mut(trigger.target())?;
+    if list.filter != *filter {
+        list.filter.clone_from(filter);
+    }
+    Ok(())
+}
+
+fn sort_button(
+    trigger: Trigger<Pointer<Click>>,
+    button_q: Query<&SortButton>,
+    mut list_q: Query<&mut SaveList>,
+) -> Result {
+    let button = button_q.get(trigger.target())?;
+    let mut list = list_q.get_mut(button.list)?;
+
+    if list.sort == button.sort {
+        list.descending = !list.descending;
+    } else {
+        list.sort = button.sort;
+        list.descending = button.sort != SaveSort::Name;
+    }
+    Ok(())
+}
+
+fn rename_button(
+    trigger: Trigger<Pointer<Click>>,
+    save_q: Query<&SaveItem>,
+    list_q: Query<&mut SaveList>,
+) -> Result {
+    start_edit(trigger, save_q, list_q, SaveEdit::Rename)
+}
+
+fn delete_button(
+    trigger: Trigger<Pointer<Click>>,
+    save_q: Query<&SaveItem>,
+    list_q: Query<&mut SaveList>,
+) -> Result {
+    start_edit(trigger, save_q, list_q, SaveEdit::Delete)
+}
+
+fn start_edit(
+    trigger: Trigger<Pointer<Click>>,
+    save_q: Query<&SaveItem>,
+    mut list_q: Query<&mut SaveList>,
+    edit: SaveEdit,
+) -> Result {
+    let item = save_q.get(trigger.target())?;
+    list_q.get_mut(item.list)?.editing = Some((item.metadata.key.clone(), edit));
+    Ok(())
+}
+
+fn cancel_edit_button(
+    trigger: Trigger<Pointer<Click>>,
+    save_q: Query<&SaveItem>,
+    mut list_q: Query<&mut SaveList>,
+) -> Result {
+    let item = save_q.get(trigger.target())?;
+    list_q.get_mut(item.list)?.editing = None;
+    Ok(())
+}
+
+fn confirm_delete_button(
+    trigger: Trigger<Pointer<Click>>,
+    save_q: Query<&SaveItem>,
+    store: Res<Store>,
+    callback: Res<CallbackSender>,
+) -> Result {
+    let item = save_q.get(trigger.target())?;
+    let name = item.metadata.name.clone();
+    let key = item.metadata.key.clone();
+
+    let store = store.clone();
+    let callback = callback.clone();
+    spawn_io(async move {
+        let res = match store.delete(&key).await {
+            Ok(true) => Ok(format!("Deleted '{name}'")),
+            Ok(false) => Err(format!("save '{name}' not found").into()),
+            Err(error) => Err(error),
+        };
+        callback.run_system_cached_with(on_manage_complete, res);
+    });
+
+    Ok(())
+}
+
+fn rename_submit(
+    mut trigger: Trigger<FormSubmit>,
+    form_q: Query<(&Form, &SaveItem)>,
+    store: Res<Store>,
+    callback: Res<CallbackSender>,
+) -> Result {
+    trigger.propagate(false);
+
+    let (form, item) = form_q.get(trigger.target())?;
+    let name = item.metadata.name.clone();
+    let key = item.metadata.key.clone();
+    let new_name = form.value::<RenameForm>()?.name;
+
+    let store = store.clone();
+    let callback = callback.clone();
+    spawn_io(async move {
+        let res = rename_save(&store, &key, &name, &new_name).await;
+        callback.run_system_cached_with(on_manage_complete, res);
+    });
+
+    Ok(())
+}
+
+fn duplicate_button(
+    trigger: Trigger<Pointer<Click>>,
+    save_q: Query<&SaveItem>,
+    store: Res<Store>,
+    callback: Res<CallbackSender>,
+) -> Result {
+    let item = save_q.get(trigger.target())?;
+    let name = item.metadata.name.clone();
+    let key = item.metadata.key.clone();
+
+    let store = store.clone();
+    let callback = callback.clone();
+    spawn_io(async move {
+        let res = duplicate_save(&store, &key, &name).await;
+        callback.run_system_cached_with(on_manage_complete, res);
+    });
+
+    Ok(())
+}
+
+fn on_manage_complete(
+    In(res): In<Result<String>>,
+    mut message_e: EventWriter<Message>,
+    store: Res<Store>,
+    callback: Res<CallbackSender>,
+) {
+    match res {
+        Ok(message) => {
+            info!("{message}");
+            message_e.write(Message::info(message));
+        }
+        Err(error) => {
+            error!("Failed to update save: {error}");
+            message_e.write(Message::error(&error));
+        }
+    }
+
+    let store = store.clone();
+    let callback = callback.clone();
+    spawn_io(async move {
+        let res = list_saves(&store).await;
+        callback.run_system_cached_with(on_reload_complete, res);
+    });
+
+    fn on_reload_complete(
+        In(res): In<Result<SaveListing>>,
+        mut list_q: Query<&mut SaveList>,
+        mut message_e: EventWriter<Message>,
+        mut images: ResMut<Assets<Image>>,
+    ) {
+        match res {
+            Ok(listing) => {
+                for mut list in &mut list_q {
+                    list.set_listing(listing.clone(), &mut images);
+                    list.editing = None;
+                }
+            }
+            Err(error) => {
+                error!("Failed to load saves: {error}");
+                message_e.write(Message::error(&error));
+            }
+        }
+    }
+}
+
+async fn list_saves(store: &Store) -> Result<SaveListing> {
+    let items = store.iter_with_info("saves").await?;
+
+    let mut thumbnails = Vec::new();
+    for (metadata, _) in &items {
+        let thumbnail_key = Sidecar::Thumbnail.key(&metadata.key);
+        let res = match store.try_get_bytes(&thumbnail_key).await {
+            Ok(Some(bytes)) => thumbnail::decode(&bytes),
+            Ok(None) => continue,
+            Err(error) => Err(error),
+        };
+
+        match res {
+            Ok(image) => thumbnails.push((metadata.key.clone(), image)),
+            Err(error) => warn!("Failed to load thumbnail '{thumbnail_key}': {error}"),
+        }
+    }
+
+    Ok(SaveListing { items, thumbnails })
+}
+
+async fn rename_save(store: &Store, key: &str, name: &str, new_name: &str) -> Result<String> {
+    let new_key = renamed_key(key, new_name);
+    if new_name.is_empty() {
+        return Err("empty name".into());
+    }
+    if new_key == key {
+        return Ok(format!("Renamed '{name}' to '{new_name}'"));
+    }
+    if store
+        .iter("saves")
+        .await?
+        .iter()
+        .any(|m| m.name == new_name && m.key != key)
+    {
+        return Err(format!("a save named '{new_name}' already exists").into());
+    }
+    if !store.rename(key, &new_key).await? {
+        return Err(format!("save '{name}' not found").into());
+    }
+    Ok(format!("Renamed '{name}' to '{new_name}'"))
+}
+
+async fn duplicate_save(store: &Store, key: &str, name: &str) -> Result<String> {
+    let new_name = unused_name(store, &format!("{name} copy")).await?;
+
+    if !store.duplicate(key, &renamed_key(key, &new_name)).await? {
+        return Err(format!("save '{name}' not found").into());
+    }
+    Ok(format!("Duplicated '{name}' as '{new_name}'"))
+}
+
+/// Writes a save to a file chosen by the user, returning the path it was written to.
+async fn export_save(
+    store: &Store,
+    key: &str,
+    name: &str,
+    path: &str,
+    kind: ExportKind,
+) -> Result<String> {
+    let (file_name, bytes) = match kind {
+        ExportKind::Archive => (
+            format!("{name}.{}", Archive::EXTENSION),
+            store.export(key).await?.to_bytes(),
+        ),
+        ExportKind::Json => {
+            let save = store.get::<VersionedSave>(key).await?;
+            (
+                format!("{name}.{}", Format::JSON_EXTENSION),
+                Format::Json.encode(&save)?,
+            )
+        }
+    };
+    pb_store::export_file(path, &file_name, bytes).await
+}
+
+/// Reads a save archive chosen by the user and adds it to the saves, returning `None` if the user
+/// didn't choose a file.
+async fn import_save(store: &Store, path: &str) -> Result<Option<String>> {
+    let Some((file_name, bytes)) = pb_store::import_file(path).await? else {
+        return Ok(None);
+    };
+
+    let archive = Archive::from_bytes(&bytes)
+        .map_err(|error| format!("failed to import '{file_name}': {error}"))?;
+    archive
+        .decode::<VersionedSave>()
+        .map_err(|error| format!("'{file_name}' does not contain a valid save: {error}"))?;
+
+    let stem = match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => &file_name,
+    };
+    let name = unused_name(store, stem).await?;
+    let key = format!("saves/{name}.{}", archive.format().extension());
+    store.import(&key, archive).await?;
+    Ok(Some(format!("Imported '{file_name}' as '{name}'")))
+}
+
+/// Finds a name for a new save which doesn't clash with an existing one, by adding a number to
+/// `name` if necessary.
+async fn unused_name(store: &Store, name: &str) -> Result<String> {
+    let saves = store.iter("saves").await?;
+    Ok((1..)
+        .map(|n| match n {
+            1 => name.to_owned(),
+            n => format!("{name} {n}"),
+        })
+        .find(|new_name| saves.iter().all(|m| m.name != *new_name))
+        .unwrap())
+}
+
+/// The key for a save renamed to `name`, which keeps the format of the original.
+fn renamed_key(key: &str, name: &str) -> SmolStr {
+    match key.rsplit_once('.') {
+        Some((_, extension)) => format!("saves/{name}.{extension}").into(),
+        None => format!("saves/{name}").into(),
+    }
+}
+
+fn format_play_time(play_time: Duration) -> String {
+    let minutes = play_time.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path};
+
+    use bevy::tasks::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let store = Store::memory();
+        let save = SaveModel {
+            pawns: vec![],
+            maps: vec![],
+            items: vec![],
+            groups: vec![],
+            tasks: vec![],
+            regime: default(),
+            clock: default(),
+            statistics: default(),
+            seed: None,
+        };
+
+        block_on(async {
+            write_save(&store, &save_key("test"), save, 0)
+                .await
+                .unwrap();
+
+            let saves = store.iter_with_info::<SaveInfo>("saves").await.unwrap();
+            assert_eq!(saves.len(), 1);
+            let (metadata, info) = &saves[0];
+            assert_eq!(metadata.name, "test");
+            assert_eq!(Format::from_key(&metadata.key), Format::Binary);
+            assert_eq!(info.as_ref().map(|info| info.pawns), Some(0));
+            let thumbnail = store
+                .try_get_bytes(&Sidecar::Thumbnail.key(&metadata.key))
+                .await
+                .unwrap();
+            assert!(thumbnail.is_some());
+
+            let VersionedSave(loaded) = store.get(&metadata.key).await.unwrap();
+            assert!(loaded.pawns.is_empty());
+        });
+    }
+
+    fn empty_save() -> SaveModel {
+        SaveModel {
+            pawns: vec![],
+            maps: vec![],
+            items: vec![],
+            groups: vec![],
+            tasks: vec![],
+            regime: default(),
+            clock: default(),
+            statistics: default(),
+            seed: None,
+        }
+    }
+
+    #[test]
+    fn test_replace_legacy_save() {
+        let store = Store::memory();
+
+        block_on(async {
+            write_save(&store, "saves/foo.json", empty_save(), 0)
+                .await
+                .unwrap();
+            write_save(&store, "saves/bar.json", empty_save(), 0)
+                .await
+                .unwrap();
+
+            replace_save(&store, "foo", &save_key("foo"), empty_save())
+                .await
+                .unwrap();
+            let keys: Vec<_> = store
+                .iter("saves")
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|metadata| metadata.key)
+                .collect();
+            assert_eq!(keys, ["saves/bar.json", "saves/foo.sav"]);
+            assert!(
+                store
+                    .try_get_bytes(&Sidecar::Info.key("saves/foo.json"))
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+
+            let error = rename_save(&store, "saves/bar.json", "bar", "foo")
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("already exists"));
+        });
+    }
+
+    #[test]
+    fn test_export() {
+        let store = Store::memory();
+        let dir = env::temp_dir().join(format!("pb-ui-export-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_str = dir.to_str().unwrap();
+
+        block_on(async {
+            write_save(&store, "saves/foo.sav", empty_save(), 0)
+                .await
+                .unwrap();
+
+            let path = export_save(&store, "saves/foo.sav", "foo", dir_str, ExportKind::Json)
+                .await
+                .unwrap();
+            assert_eq!(Path::new(&path), dir.join("foo.json"));
+            let exported = Store::dir(&dir);
+            exported.get::<VersionedSave>("foo.json").await.unwrap();
+
+            let path = dir.join("shared.pbsave");
+            let path_str = path.to_str().unwrap();
+            let res = export_save(
+                &store,
+                "saves/foo.sav",
+                "foo",
+                path_str,
+                ExportKind::Archive,
+            )
+            .await
+            .unwrap();
+            assert_eq!(res, path_str);
+            let archive = Archive::from_bytes(&fs::read(&path).unwrap()).unwrap();
+            assert_eq!(archive.format(), Format::Binary);
+            assert!(archive.sidecar(Sidecar::Info).is_some());
+        });
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sort_and_filter() {
+        let item = |name: &str, pawns| {
+            let info = SaveInfo { pawns, ..default() };
+            (Metadata::new(name), Some(info))
+        };
+        let names = |list: &SaveList| {
+            list.visible_items()
+                .into_iter()
+                .map(|(metadata, _)| metadata.name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let mut list = SaveList::new(SaveAction::Load);
+        list.items = vec![
+            item("alcatraz", 3),
+            item("autosave", 5),
+            item("bastille", 1),
+        ];
+
+        list.sort = SaveSort::Pawns;
+        assert_eq!(names(&list), ["autosave", "alcatraz", "bastille"]);
+
+        list.filter = "S".to_owned();
+        assert_eq!(names(&list), ["autosave", "bastille"]);
+
+        list.sort = SaveSort::Name;
+        list.descending = false;
+        assert_eq!(names(&list), ["autosave", "bastille"]);
+        list.descending = true;
+        assert_eq!(names(&list), ["bastille", "autosave"]);
+    }
+}
@@ -3,6 +3,7 @@ use std::time::Duration;
 use bevy::prelude::*;
 
 use pb_assets::AssetHandles;
+use pb_engine::security::SecurityEvent;
 
 use crate::{layout::Layout, theme::Theme, widget::UiBuilder};
 
@@ -93,6 +94,26 @@ pub fn spawn_messages(
     }
 }
 
+pub fn security_messages(
+    mut security_e: EventReader<SecurityEvent>,
+    mut message_e: EventWriter<Message>,
+) {
+    for event in security_e.read() {
+        message_e.write(match *event {
+            SecurityEvent::EscapeStarted { .. } => continue,
+            SecurityEvent::EscapeSpotted { number, .. } => {
+                Message::info(format!("Prisoner #{number} was caught trying to escape"))
+            }
+            SecurityEvent::Escaped { number, .. } => {
+                Message::info(format!("Prisoner #{number} has escaped"))
+            }
+            SecurityEvent::ContrabandFound { kind, .. } => {
+                Message::info(format!("Guards confiscated contraband ({kind:?})"))
+            }
+        });
+    }
+}
+
 pub fn despawn_messages(
     mut commands: Commands,
     time: Res<Time<Real>>,
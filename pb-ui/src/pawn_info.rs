@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+use pb_assets::AssetHandles;
+use pb_engine::{
+    item::{Inventory, Item},
+    pawn::{
+        ai::{Actor, trace::BehaviourTrace},
+        health::Health,
+        mood::Mood,
+        role::RoleQuery,
+    },
+};
+use pb_store::Store;
+use pb_util::callback::{CallbackSender, spawn_io};
+
+use crate::{layout::Layout, message::Message, theme::Theme, widget::UiBuilder};
+
+/// The number of the most recent trace entries shown in the panel.
+const TRACE_LINES: usize = 12;
+
+/// Marks a pawn selected by the player.
+#[derive(Clone, Copy, Debug, Component)]
+pub struct Selected;
+
+#[derive(Clone, Copy, Debug, Component)]
+pub struct PawnInfoText {
+    pawn: Entity,
+}
+
+impl<'w> UiBuilder<'w, '_> {
+    pub fn pawn_info_root(&mut self) -> UiBuilder<'w, '_> {
+        self.container(Node {
+            position_type: PositionType::Absolute,
+            margin: UiRect::new(Val::Auto, Val::ZERO, Val::ZERO, Val::Auto),
+            ..default()
+        })
+    }
+
+    pub fn pawn_info(&mut self, theme: &Theme, assets: &AssetHandles, pawn: Entity) {
+        let mut panel = self.panel(
+            theme,
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gutter,
+                ..default()
+            },
+        );
+
+        panel.spawn((
+            Text::default(),
+            theme.normal_text.clone(),
+            PawnInfoText { pawn },
+        ));
+
+        let mut buttons = panel.container(Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Row,
+            column_gap: theme.gutter,
+            ..default()
+        });
+        buttons
+            .button(theme, assets, "Toggle trace", default())
+            .on_click(
+                move |_: Trigger<Pointer<Click>>,
+                      mut commands: Commands,
+                      trace_q: Query<(), With<BehaviourTrace>>|
+                      -> Result {
+                    if trace_q.contains(pawn) {
+                        commands.entity(pawn).remove::<BehaviourTrace>();
+                    } else {
+                        commands.entity(pawn).insert(BehaviourTrace::default());
+                    }
+                    Ok(())
+                },
+            );
+        buttons
+            .button(theme, assets, "Dump trace", default())
+            .on_click(
+                move |_: Trigger<Pointer<Click>>,
+                      trace_q: Query<&BehaviourTrace>,
+                      store: Res<Store>,
+                      callback: Res<CallbackSender>|
+                      -> Result {
+                    let trace = trace_q
+                        .get(pawn)
+                        .map_err(|_| "pawn is not being traced")?
+                        .clone();
+
+                    let key = format!("traces/{pawn}.json");
+                    let store = store.clone();
+                    let callback = callback.clone();
+                    spawn_io(async move {
+                        let res = store.set(&key, trace).await.map(|()| key);
+                        callback.run_system_cached_with(on_dump_complete, res);
+                    });
+                    Ok(())
+                },
+            );
+    }
+}
+
+fn on_dump_complete(In(res): In<Result<String>>, mut message_e: EventWriter<Message>) {
+    match res {
+        Ok(key) => {
+            message_e.write(Message::info(format!("Saved trace to '{key}'")));
+        }
+        Err(error) => {
+            error!("Failed to save trace: {error}");
+            message_e.write(Message::error(&error));
+        }
+    }
+}
+
+/// Shows the info panel while exactly one pawn is selected.
+pub fn update_panel(
+    mut commands: Commands,
+    layout: Res<Layout>,
+    theme: Res<Theme>,
+    assets: Res<AssetHandles>,
+    selected_q: Query<Entity, With<Selected>>,
+    text_q: Query<&PawnInfoText>,
+) {
+    let selected = selected_q.single().ok();
+    let shown = text_q.iter().next().map(|text| text.pawn);
+    if selected == shown {
+        return;
+    }
+
+    commands
+        .entity(layout.pawn_info)
+        .despawn_related::<Children>();
+    if let Some(pawn) = selected {
+        UiBuilder::new(commands, layout.pawn_info).pawn_info(&theme, &assets, pawn);
+    }
+}
+
+pub fn update_text(
+    mut text_q: Query<(&mut Text, &PawnInfoText)>,
+    pawn_q: Query<(
+        RoleQuery,
+        &Health,
+        &Mood,
+        &Actor,
+        Option<&Inventory>,
+        Option<&BehaviourTrace>,
+    )>,
+    item_q: Query<&Item>,
+) {
+    for (mut text, info) in &mut text_q {
+        let Ok((role, health, mood, actor, inventory, trace)) = pawn_q.get(info.pawn) else {
+            continue;
+        };
+
+        let role = match (role.prisoner, role.role()) {
+            (Some(prisoner), _) => format!("Prisoner #{}", prisoner.number),
+            (None, Some(role)) => format!("{role:?}"),
+            (None, None) => "No role".to_owned(),
+        };
+        let task = actor
+            .task()
+            .map_or_else(|| "idle".to_owned(), |task| task.to_string());
+        let items = inventory
+            .map(|inventory| {
+                item_q
+                    .iter_many(inventory.iter())
+                    .map(|item| format!("{:?}", item.kind))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .filter(|items| !items.is_empty())
+            .unwrap_or_else(|| "none".to_owned());
+        let mut content = format!(
+            "Pawn {}\n{role}\nInjury: {:.0}%\nMood: {:+.2}\nTask: {task}\nItems: {items}\n",
+            info.pawn,
+            health.injury() * 100.,
+            mood.get(),
+        );
+
+        match trace {
+            Some(trace) => {
+                content.push_str("\nTrace:");
+                for entry in trace.entries().rev().take(TRACE_LINES) {
+                    content.push('\n');
+                    content.push_str(&entry.to_string());
+                }
+            }
+            None => content.push_str("\nTrace: off"),
+        }
+
+        if text.0 != content {
+            text.0 = content;
+        }
+    }
+}
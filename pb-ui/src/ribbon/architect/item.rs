@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use pb_engine::{
+    EngineState,
+    item::{Item, ItemKind},
+};
+
+use crate::{
+    action::Action,
+    input::{cancel::Cancellable, picking::point::ClickPoint},
+};
+
+pub fn item(mut commands: Commands, kind: ItemKind) -> Result {
+    commands.spawn((ItemAction { kind }, children![Observer::new(click_point)]));
+    Ok(())
+}
+
+#[derive(Debug, Component, TypePath)]
+#[require(Action, Cancellable, Name::new(ItemAction::type_path()))]
+pub struct ItemAction {
+    kind: ItemKind,
+}
+
+fn click_point(
+    trigger: Trigger<ClickPoint>,
+    mut commands: Commands,
+    action: Single<&ItemAction>,
+    engine_state: Res<State<EngineState>>,
+) {
+    let &EngineState::Running(root) = engine_state.get() else {
+        warn!("engine not running");
+        return;
+    };
+
+    commands.spawn((Item::new(action.kind).bundle(trigger.point), ChildOf(root)));
+}
@@ -1,2 +1,5 @@
+pub mod item;
 pub mod map;
+pub mod patrol;
 pub mod pawn;
+pub mod room;
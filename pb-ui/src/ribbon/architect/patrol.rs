@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use pb_engine::map::patrol::{PatrolRoute, Waypoint};
+use pb_render::wall::VisibleMaps;
+
+use crate::{
+    action::Action,
+    input::{cancel::Cancellable, picking::point::ClickPoint},
+};
+
+pub fn add_patrol_route(_: Trigger<Pointer<Click>>, mut commands: Commands) -> Result {
+    commands.spawn((
+        AddPatrolRouteAction::default(),
+        children![Observer::new(click_point)],
+    ));
+    Ok(())
+}
+
+pub fn clear_patrol_routes(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    visible_map: Res<VisibleMaps>,
+    route_q: Query<(Entity, &ChildOf), With<PatrolRoute>>,
+) -> Result {
+    let map = visible_map.source().ok_or("map should be visible")?;
+    for (id, parent) in &route_q {
+        if parent.parent() == map {
+            commands.entity(id).despawn();
+        }
+    }
+    Ok(())
+}
+
+/// Adds a waypoint to a new patrol route for each point clicked, until cancelled.
+#[derive(Default, Debug, Component, TypePath)]
+#[require(Action, Cancellable, Name::new(AddPatrolRouteAction::type_path()))]
+pub struct AddPatrolRouteAction {
+    route: Option<Entity>,
+}
+
+fn click_point(
+    trigger: Trigger<ClickPoint>,
+    mut commands: Commands,
+    mut action: Single<&mut AddPatrolRouteAction>,
+    visible_map: Res<VisibleMaps>,
+    mut route_q: Query<&mut PatrolRoute>,
+) -> Result {
+    let waypoint = Waypoint::new(trigger.point);
+    match action.route {
+        Some(route) => route_q.get_mut(route)?.push(waypoint),
+        None => {
+            let map = visible_map.source().ok_or("map should be visible")?;
+            let route = commands
+                .spawn((PatrolRoute::new(vec![waypoint]), ChildOf(map)))
+                .id();
+            action.route = Some(route);
+        }
+    }
+    Ok(())
+}
@@ -1,23 +1,39 @@
 use bevy::prelude::*;
-use pb_engine::{EngineState, pawn::PawnBundle};
+use pb_engine::{
+    EngineState,
+    pawn::{
+        MovementStats, PawnBundle,
+        role::{Guard, Job, Prisoner, Role, Shift, Worker},
+    },
+};
 
 use crate::{
     action::Action,
     input::{cancel::Cancellable, picking::point::ClickPoint},
 };
 
-pub fn pawn(_: Trigger<Pointer<Click>>, mut commands: Commands) -> Result {
-    commands.spawn((PawnAction, children![Observer::new(click_point)]));
+pub fn pawn(mut commands: Commands, kind: PawnKind) -> Result {
+    commands.spawn((PawnAction { kind }, children![Observer::new(click_point)]));
     Ok(())
 }
 
-#[derive(Default, Debug, Component, TypePath)]
+#[derive(Debug, Component, TypePath)]
 #[require(Action, Cancellable, Name::new(PawnAction::type_path()))]
-pub struct PawnAction;
+pub struct PawnAction {
+    kind: PawnKind,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum PawnKind {
+    Prisoner,
+    Guard(Shift),
+    Worker(Job),
+}
 
 fn click_point(
     trigger: Trigger<ClickPoint>,
     mut commands: Commands,
+    action: Single<&PawnAction>,
     engine_state: Res<State<EngineState>>,
 ) {
     let &EngineState::Running(root) = engine_state.get() else {
@@ -25,5 +41,25 @@ fn click_point(
         return;
     };
 
-    commands.spawn((PawnBundle::new(trigger.point, 0.), ChildOf(root)));
+    let pawn = PawnBundle::new(trigger.point, 0.);
+    match action.kind {
+        PawnKind::Prisoner => commands.spawn((
+            pawn,
+            MovementStats::for_role(Some(Role::Prisoner)),
+            Prisoner::default(),
+            ChildOf(root),
+        )),
+        PawnKind::Guard(shift) => commands.spawn((
+            pawn,
+            MovementStats::for_role(Some(Role::Guard)),
+            Guard { shift },
+            ChildOf(root),
+        )),
+        PawnKind::Worker(job) => commands.spawn((
+            pawn,
+            MovementStats::for_role(Some(Role::Worker)),
+            Worker { job },
+            ChildOf(root),
+        )),
+    };
 }
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use pb_engine::map::{
+    Map,
+    room::{Designation, Room},
+};
+use pb_render::wall::VisibleMaps;
+
+use crate::{
+    action::Action,
+    input::{cancel::Cancellable, picking::point::ClickPoint},
+};
+
+pub fn designate_room(mut commands: Commands, designation: Option<Designation>) -> Result {
+    commands.spawn((
+        DesignateRoomAction { designation },
+        children![Observer::new(click_point)],
+    ));
+    Ok(())
+}
+
+#[derive(Debug, Component, TypePath)]
+#[require(Action, Cancellable, Name::new(DesignateRoomAction::type_path()))]
+pub struct DesignateRoomAction {
+    designation: Option<Designation>,
+}
+
+fn click_point(
+    trigger: Trigger<ClickPoint>,
+    mut commands: Commands,
+    action: Single<&DesignateRoomAction>,
+    visible_map: Res<VisibleMaps>,
+    map_q: Query<&Map>,
+    room_q: Query<&Room>,
+) -> Result {
+    let map = map_q.get(visible_map.source().ok_or("map should be visible")?)?;
+    let Some((room, _)) = map.containing_room(trigger.point, None) else {
+        return Ok(());
+    };
+    if room_q.get(room)?.is_outer() {
+        return Ok(());
+    }
+
+    match action.designation {
+        Some(designation) => commands.entity(room).insert(designation),
+        None => commands.entity(room).remove::<Designation>(),
+    };
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use pb_assets::AssetHandles;
+use pb_engine::clock::{Clock, Speed};
+
+use crate::{theme::Theme, widget::UiBuilder};
+
+#[derive(Clone, Copy, Debug, Component)]
+pub struct ClockText;
+
+impl<'w> UiBuilder<'w, '_> {
+    pub fn clock(&mut self, theme: &Theme, assets: &AssetHandles) {
+        let mut container = self.container(Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: theme.gutter,
+            padding: UiRect::horizontal(theme.gutter),
+            ..default()
+        });
+
+        container.spawn((
+            Node {
+                min_width: Val::Px(160.),
+                ..default()
+            },
+            Text::default(),
+            theme.normal_text.clone(),
+            ClockText,
+        ));
+        container.button(theme, assets, "||", default()).on_click(
+            |_: Trigger<Pointer<Click>>, mut clock: ResMut<Clock>| -> Result {
+                clock.toggle_paused();
+                Ok(())
+            },
+        );
+        for speed in Speed::ALL {
+            container
+                .button(theme, assets, speed.to_string(), default())
+                .on_click(
+                    move |_: Trigger<Pointer<Click>>, mut clock: ResMut<Clock>| -> Result {
+                        clock.set_speed(speed);
+                        Ok(())
+                    },
+                );
+        }
+    }
+}
+
+pub fn update(clock: Res<Clock>, mut text_q: Query<(&mut Text, Ref<ClockText>)>) {
+    for (mut text, marker) in &mut text_q {
+        if clock.is_changed() || marker.is_added() {
+            text.0 = if clock.is_paused() {
+                format!("{} (paused)", *clock)
+            } else {
+                format!("{} ({})", *clock, clock.speed())
+            };
+        }
+    }
+}
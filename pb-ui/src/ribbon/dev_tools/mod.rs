@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use pb_engine::dev::DevSettings;
+use pb_engine::{dev::DevSettings, pawn::ai::path::PathQueryConfig};
 
 pub mod path_stress_test;
 
@@ -9,7 +9,21 @@ pub fn toggle_draw_paths(_: Trigger<Pointer<Click>>, mut settings: ResMut<DevSet
     Ok(())
 }
 
+pub fn toggle_avoidance(_: Trigger<Pointer<Click>>, mut config: ResMut<PathQueryConfig>) -> Result {
+    config.avoidance = !config.avoidance;
+    info!("pawn avoidance enabled: {}", config.avoidance);
+    Ok(())
+}
+
 pub fn toggle_draw_meshes(_: Trigger<Pointer<Click>>, mut settings: ResMut<DevSettings>) -> Result {
     settings.draw_meshes = !settings.draw_meshes;
     Ok(())
 }
+
+pub fn toggle_draw_reservations(
+    _: Trigger<Pointer<Click>>,
+    mut settings: ResMut<DevSettings>,
+) -> Result {
+    settings.draw_reservations = !settings.draw_reservations;
+    Ok(())
+}
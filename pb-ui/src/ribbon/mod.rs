@@ -1,11 +1,21 @@
 pub mod architect;
+pub mod clock;
 pub mod dev_tools;
+pub mod schedule;
 
 use bevy::prelude::*;
 
 use pb_assets::AssetHandles;
+use pb_engine::{
+    item::ItemKind,
+    map::room::Designation,
+    pawn::role::{Job, Shift},
+    regime::Regime,
+};
 
-use crate::{UiState, layout::Layout, theme::Theme, widget::UiBuilder};
+use crate::{
+    UiState, layout::Layout, ribbon::architect::pawn::PawnKind, theme::Theme, widget::UiBuilder,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum RibbonState {
@@ -77,6 +87,7 @@ impl<'w> UiBuilder<'w, '_> {
         container.ribbon_button(theme, assets, RibbonButton::Schedule);
         container.ribbon_button(theme, assets, RibbonButton::Manage);
         container.ribbon_button(theme, assets, RibbonButton::DevTools);
+        container.clock(theme, assets);
     }
 
     fn ribbon_button(&mut self, theme: &Theme, assets: &AssetHandles, button: RibbonButton) {
@@ -98,17 +109,24 @@ impl<'w> UiBuilder<'w, '_> {
                   theme: Res<Theme>,
                   assets: Res<AssetHandles>,
                   layout: Res<Layout>,
+                  regime: Res<Regime>,
                   panels: Query<(Entity, &RibbonPanel)>| {
-                button.on_click(commands, theme, assets, layout, panels)
+                button.on_click(commands, theme, assets, layout, regime, panels)
             },
         );
     }
 
-    fn ribbon_panel(&mut self, theme: &Theme, assets: &AssetHandles, kind: RibbonPanel) {
+    fn ribbon_panel(
+        &mut self,
+        theme: &Theme,
+        assets: &AssetHandles,
+        regime: &Regime,
+        kind: RibbonPanel,
+    ) {
         let mut panel = match kind {
             RibbonPanel::Architect => self.ribbon_architect_panel(theme, assets),
             RibbonPanel::Staff => self.ribbon_staff_panel(theme, assets),
-            RibbonPanel::Schedule => self.ribbon_schedule_panel(theme, assets),
+            RibbonPanel::Schedule => self.ribbon_schedule_panel(theme, assets, regime),
             RibbonPanel::Manage => self.ribbon_manage_panel(theme, assets),
             RibbonPanel::DevTools => self.ribbon_dev_tools_panel(theme, assets),
         };
@@ -146,23 +164,101 @@ impl<'w> UiBuilder<'w, '_> {
         icon_grid
             .tile_button(theme, "Build door", assets.ribbon_button_door_image.clone())
             .on_click(architect::map::add_door::add_door);
+        icon_grid.pawn_button(theme, assets, "Prisoner", PawnKind::Prisoner);
+        icon_grid.designate_room_button(theme, assets, "Cell", Some(Designation::Cell));
+        icon_grid.designate_room_button(theme, assets, "Canteen", Some(Designation::Canteen));
+        icon_grid.designate_room_button(theme, assets, "Workshop", Some(Designation::Workshop));
+        icon_grid.designate_room_button(theme, assets, "Yard", Some(Designation::Yard));
+        icon_grid.designate_room_button(theme, assets, "Infirmary", Some(Designation::Infirmary));
+        icon_grid.designate_room_button(theme, assets, "Clear room", None);
+        icon_grid.item_button(theme, assets, "Book", ItemKind::Book);
+        icon_grid.item_button(theme, assets, "Phone", ItemKind::Phone);
+        icon_grid.item_button(theme, assets, "Shiv", ItemKind::Shiv);
+
+        icon_grid
+    }
+
+    fn ribbon_staff_panel(&mut self, theme: &Theme, assets: &AssetHandles) -> UiBuilder<'w, '_> {
+        let mut icon_grid = self.container(Node {
+            padding: UiRect::new(theme.gutter, theme.gutter, Val::ZERO, theme.gutter),
+            display: Display::Grid,
+            grid_auto_flow: GridAutoFlow::Column,
+            grid_auto_columns: vec![GridTrack::max_content()],
+            grid_auto_rows: vec![GridTrack::max_content()],
+            row_gap: theme.gutter,
+            column_gap: theme.gutter,
+            align_items: AlignItems::Center,
+            ..default()
+        });
+
+        icon_grid.pawn_button(theme, assets, "Day guard", PawnKind::Guard(Shift::Day));
+        icon_grid.pawn_button(theme, assets, "Night guard", PawnKind::Guard(Shift::Night));
+        icon_grid.pawn_button(theme, assets, "Cook", PawnKind::Worker(Job::Cook));
+        icon_grid.pawn_button(theme, assets, "Cleaner", PawnKind::Worker(Job::Cleaner));
+        icon_grid.pawn_button(theme, assets, "Gardener", PawnKind::Worker(Job::Gardener));
+        icon_grid.pawn_button(theme, assets, "Medic", PawnKind::Worker(Job::Medic));
+        icon_grid
+            .tile_button(theme, "Patrol route", assets.pawn_image.clone())
+            .on_click(architect::patrol::add_patrol_route);
         icon_grid
-            .tile_button(theme, "Pawn", assets.pawn_image.clone())
-            .on_click(architect::pawn::pawn);
+            .tile_button(
+                theme,
+                "Clear patrols",
+                assets.ribbon_button_delete_wall_image.clone(),
+            )
+            .on_click(architect::patrol::clear_patrol_routes);
 
         icon_grid
     }
 
-    fn ribbon_staff_panel(&mut self, theme: &Theme, _assets: &AssetHandles) -> UiBuilder<'w, '_> {
-        self.panel(theme, default())
+    fn pawn_button(
+        &mut self,
+        theme: &Theme,
+        assets: &AssetHandles,
+        text: &'static str,
+        kind: PawnKind,
+    ) {
+        self.tile_button(theme, text, assets.pawn_image.clone())
+            .on_click(move |_: Trigger<Pointer<Click>>, commands: Commands| {
+                architect::pawn::pawn(commands, kind)
+            });
+    }
+
+    fn designate_room_button(
+        &mut self,
+        theme: &Theme,
+        assets: &AssetHandles,
+        text: &'static str,
+        designation: Option<Designation>,
+    ) {
+        self.tile_button(theme, text, assets.ribbon_button_wall_image.clone())
+            .on_click(move |_: Trigger<Pointer<Click>>, commands: Commands| {
+                architect::room::designate_room(commands, designation)
+            });
+    }
+
+    fn item_button(
+        &mut self,
+        theme: &Theme,
+        assets: &AssetHandles,
+        text: &'static str,
+        kind: ItemKind,
+    ) {
+        self.tile_button(theme, text, assets.ribbon_button_image.clone())
+            .on_click(move |_: Trigger<Pointer<Click>>, commands: Commands| {
+                architect::item::item(commands, kind)
+            });
     }
 
     fn ribbon_schedule_panel(
         &mut self,
         theme: &Theme,
-        _assets: &AssetHandles,
+        assets: &AssetHandles,
+        regime: &Regime,
     ) -> UiBuilder<'w, '_> {
-        self.panel(theme, default())
+        let mut panel = self.container(default());
+        panel.schedule_grid(theme, assets, regime);
+        panel
     }
 
     fn ribbon_manage_panel(&mut self, theme: &Theme, _assets: &AssetHandles) -> UiBuilder<'w, '_> {
@@ -196,12 +292,22 @@ impl<'w> UiBuilder<'w, '_> {
                 assets.ribbon_button_wall_image.clone(),
             )
             .on_click(dev_tools::toggle_draw_meshes);
+        icon_grid
+            .tile_button(
+                theme,
+                "Draw Reservations",
+                assets.ribbon_button_wall_image.clone(),
+            )
+            .on_click(dev_tools::toggle_draw_reservations);
         icon_grid
             .tile_button(theme, "Spawn 1000 Pawns", assets.pawn_image.clone())
             .on_click(dev_tools::path_stress_test::spawn_1000_pawns);
         icon_grid
             .tile_button(theme, "Create path tasks", assets.pawn_image.clone())
             .on_click(dev_tools::path_stress_test::create_path_tasks);
+        icon_grid
+            .tile_button(theme, "Toggle Avoidance", assets.pawn_image.clone())
+            .on_click(dev_tools::toggle_avoidance);
 
         icon_grid
     }
@@ -234,6 +340,7 @@ impl RibbonButton {
         theme: Res<Theme>,
         assets: Res<AssetHandles>,
         layout: Res<Layout>,
+        regime: Res<Regime>,
         panel_q: Query<(Entity, &RibbonPanel)>,
     ) -> Result {
         let requested_panel = self.panel();
@@ -244,7 +351,12 @@ impl RibbonButton {
             }
         }
 
-        UiBuilder::new(commands, layout.ribbon).ribbon_panel(&theme, &assets, requested_panel);
+        UiBuilder::new(commands, layout.ribbon).ribbon_panel(
+            &theme,
+            &assets,
+            &regime,
+            requested_panel,
+        );
         Ok(())
     }
 }
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use pb_assets::AssetHandles;
+use pb_engine::regime::Regime;
+
+use crate::{theme::Theme, widget::UiBuilder};
+
+impl<'w> UiBuilder<'w, '_> {
+    pub fn schedule_grid(&mut self, theme: &Theme, assets: &AssetHandles, regime: &Regime) {
+        let mut grid = self.container(Node {
+            padding: UiRect::new(theme.gutter, theme.gutter, Val::ZERO, theme.gutter),
+            display: Display::Grid,
+            grid_template_columns: RepeatedGridTrack::max_content(12),
+            row_gap: theme.gutter,
+            column_gap: theme.gutter,
+            align_items: AlignItems::Center,
+            ..default()
+        });
+
+        for (hour, activity) in regime.hours() {
+            let mut cell = grid.container(Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            });
+
+            cell.spawn((
+                Text::new(format!("{hour:02}:00")),
+                theme.normal_text.clone(),
+            ));
+            cell.button(
+                theme,
+                assets,
+                activity.to_string(),
+                Node {
+                    min_width: Val::Px(80.),
+                    ..default()
+                },
+            )
+            .on_click(
+                move |trigger: Trigger<Pointer<Click>>,
+                      mut regime: ResMut<Regime>,
+                      children_q: Query<&Children>,
+                      mut text_q: Query<&mut Text>|
+                      -> Result {
+                    let activity = regime.activity(hour).next();
+                    regime.set(hour, activity);
+
+                    let mut texts = text_q.iter_many_mut(children_q.get(trigger.target())?);
+                    while let Some(mut text) = texts.fetch_next() {
+                        text.0 = activity.to_string();
+                    }
+                    Ok(())
+                },
+            );
+        }
+    }
+}
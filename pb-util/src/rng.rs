@@ -1,16 +1,92 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::{component::Tick, system::SystemParam},
+    prelude::*,
+};
 use rand::{RngCore, SeedableRng, rngs::SmallRng};
 
-pub type LocalRng<'s> = Local<'s, Seeded<SmallRng>>;
+/// A random number generator owned by a system.
+///
+/// Generators are seeded from the OS, unless there is an [`RngSeed`] resource, in which case they
+/// are seeded from it.
+#[derive(SystemParam)]
+pub struct LocalRng<'w, 's> {
+    seed: Option<Res<'w, RngSeed>>,
+    rng: Local<'s, Seeded<SmallRng>>,
+}
+
+/// Fixes the seed of every [`LocalRng`], so they generate the same numbers on each run. Each
+/// generator is reseeded the next time it is used after this resource changes.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RngSeed(pub u64);
+
+/// The number of generators created, used to give each one a different stream of numbers from the
+/// same seed. Generators are created as systems are initialized, so the streams are assigned in the
+/// same order for the same app.
+#[derive(Resource, Default)]
+struct RngStreams(u64);
+
+pub struct Seeded<R> {
+    rng: R,
+    stream: u64,
+    seeded_at: Option<Tick>,
+}
+
+impl<R> FromWorld for Seeded<R>
+where
+    R: SeedableRng,
+{
+    fn from_world(world: &mut World) -> Self {
+        let mut streams = world.get_resource_or_init::<RngStreams>();
+        let stream = streams.0;
+        streams.0 += 1;
 
-pub struct Seeded<R>(R);
+        Seeded {
+            rng: R::from_os_rng(),
+            stream,
+            seeded_at: None,
+        }
+    }
+}
 
-impl<R> Default for Seeded<R>
+impl<R> Seeded<R>
 where
     R: SeedableRng,
 {
-    fn default() -> Self {
-        Seeded(R::from_os_rng())
+    fn reseed(&mut self, seed: Option<&Res<RngSeed>>) {
+        match seed {
+            Some(seed) if self.seeded_at != Some(seed.last_changed()) => {
+                // Spread the streams out, so nearby seeds don't share streams.
+                let stream = self.stream.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                self.rng = R::seed_from_u64(seed.0 ^ stream);
+                self.seeded_at = Some(seed.last_changed());
+            }
+            None if self.seeded_at.is_some() => {
+                self.rng = R::from_os_rng();
+                self.seeded_at = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl LocalRng<'_, '_> {
+    fn rng(&mut self) -> &mut SmallRng {
+        self.rng.reseed(self.seed.as_ref());
+        &mut self.rng.rng
+    }
+}
+
+impl RngCore for LocalRng<'_, '_> {
+    fn next_u32(&mut self) -> u32 {
+        self.rng().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng().fill_bytes(dst)
     }
 }
 
@@ -19,14 +95,14 @@ where
     R: RngCore,
 {
     fn next_u32(&mut self) -> u32 {
-        self.0.next_u32()
+        self.rng.next_u32()
     }
 
     fn next_u64(&mut self) -> u64 {
-        self.0.next_u64()
+        self.rng.next_u64()
     }
 
     fn fill_bytes(&mut self, dst: &mut [u8]) {
-        self.0.fill_bytes(dst)
+        self.rng.fill_bytes(dst)
     }
 }
@@ -4,6 +4,11 @@
 mod diagnostic;
 mod window;
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::{env, path::PathBuf};
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::app::{TaskPoolOptions, TaskPoolPlugin};
 use bevy::{
     asset::AssetMetaCheck,
     ecs::error::{GLOBAL_ERROR_HANDLER, error},
@@ -12,6 +17,8 @@ use bevy::{
 
 use pb_assets::PbAssetsPlugin;
 use pb_engine::PbEnginePlugin;
+#[cfg(not(target_arch = "wasm32"))]
+use pb_engine::determinism::Deterministic;
 use pb_render::PbRenderPlugin;
 use pb_store::PbStorePlugin;
 use pb_ui::PbUiPlugin;
@@ -24,15 +31,32 @@ fn main() -> AppExit {
 
     let mut app = App::new();
 
-    app.add_plugins(DefaultPlugins.set(window::plugin()).set(AssetPlugin {
+    #[allow(unused_mut)]
+    let mut plugins = DefaultPlugins.set(window::plugin()).set(AssetPlugin {
         meta_check: AssetMetaCheck::Never,
         ..default()
-    }))
-    .add_systems(Startup, window::set_icon);
+    });
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if deterministic() {
+        plugins = plugins.set(TaskPoolPlugin {
+            task_pool_options: TaskPoolOptions::with_num_threads(1),
+        });
+        app.insert_resource(Deterministic);
+    }
+
+    app.add_plugins(plugins)
+        .add_systems(Startup, window::set_icon);
 
     #[cfg(feature = "dev")]
     app.add_plugins(diagnostic::DiagnosticsPlugin);
 
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(dir) = data_dir() {
+        info!("Using data directory '{}'", dir.display());
+        app.insert_resource(pb_store::Store::dir(dir));
+    }
+
     app.add_plugins((
         CallbackPlugin,
         PbAssetsPlugin,
@@ -50,3 +74,25 @@ fn main() -> AppExit {
 
     app.run()
 }
+
+/// Returns the directory passed with `--data-dir`, which replaces the user's data directory for
+/// saves and settings.
+#[cfg(not(target_arch = "wasm32"))]
+fn data_dir() -> Option<PathBuf> {
+    let mut args = env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--data-dir" {
+            return args.next().map(PathBuf::from);
+        } else if let Some(dir) = arg.to_str().and_then(|arg| arg.strip_prefix("--data-dir=")) {
+            return Some(dir.into());
+        }
+    }
+    None
+}
+
+/// Returns whether `--deterministic` was passed, which makes the simulation reproducible at the
+/// cost of running it on a single thread.
+#[cfg(not(target_arch = "wasm32"))]
+fn deterministic() -> bool {
+    env::args_os().skip(1).any(|arg| arg == "--deterministic")
+}